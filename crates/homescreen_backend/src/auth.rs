@@ -0,0 +1,236 @@
+use crate::proxy::ProxyAuthConfig;
+use actix_web::{
+    body::{EitherBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+    http::{header::AUTHORIZATION, Method},
+    middleware::Next,
+    web::Data,
+    Error, HttpMessage,
+};
+use chrono::{TimeDelta, Utc};
+use homescreen_data::prelude::*;
+use homescreen_errors::prelude::*;
+use log::{info, warn};
+
+/// The cookie browsers that have logged in send their session in
+pub const SESSION_COOKIE: &str = "homescreen_session";
+/// How long a browser stays logged in for
+pub const SESSION_LENGTH: TimeDelta = TimeDelta::days(30);
+
+/// The scope a request needs, reading and logging in are open to anyone while changing anything
+/// needs a token or session and managing tokens or users needs an admin one. Personal homescreens
+/// only need someone to be logged in since they only change what that person sees
+///
+/// ```rust
+/// # use actix_web::http::Method;
+/// # use homescreen_backend::prelude::*;
+/// # use homescreen_data::prelude::*;
+/// assert_eq!(auth::required_scope(&Method::GET, "/websites"), None);
+/// assert_eq!(auth::required_scope(&Method::POST, "/login"), None);
+/// assert_eq!(auth::required_scope(&Method::PUT, "/websites"), Some(Scope::Write));
+/// assert_eq!(auth::required_scope(&Method::GET, "/tokens"), Some(Scope::Admin));
+/// assert_eq!(auth::required_scope(&Method::DELETE, "/users/sam"), Some(Scope::Admin));
+/// assert_eq!(auth::required_scope(&Method::PUT, "/personal/websites"), Some(Scope::Read));
+/// ```
+///
+/// `path` has to be the route the request is matched against rather than the raw path, since
+/// `/%74okens` ends up at the same handlers as `/tokens`
+pub fn required_scope(method: &Method, path: &str) -> Option<Scope> {
+    let under = |prefix: &str| {
+        path.strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+    };
+
+    if under("/tokens") || under("/users") {
+        Some(Scope::Admin)
+    } else if under("/personal") {
+        Some(Scope::Read)
+    } else if method == Method::GET
+        || method == Method::HEAD
+        || path == "/login"
+        || path == "/logout"
+    {
+        None
+    } else {
+        Some(Scope::Write)
+    }
+}
+
+/// Creates a token called `name` and returns it alongside the secret callers have to send, the
+/// secret isn't stored anywhere so this is the only time it can be seen
+pub async fn issue_token<S: Store>(
+    store: &S,
+    name: String,
+    scope: Scope,
+) -> HomescreenResult<(ApiToken, String)> {
+    let token = ApiToken::new(name, scope, Utc::now());
+    let secret = ApiToken::generate();
+
+    store
+        .create_token(token.clone(), ApiToken::hash(&secret))
+        .await?;
+
+    Ok((token, secret))
+}
+
+/// Creates the user called `name`, or changes the password and role of the existing one, returns
+/// whether they were created
+pub async fn save_user<S: Store>(
+    store: &S,
+    name: String,
+    password: &str,
+    role: Role,
+) -> HomescreenResult<bool> {
+    let password_hash = User::hash_password(password)?;
+
+    store
+        .create_or_update_user(User::new(name, role, Utc::now()), password_hash)
+        .await
+}
+
+/// Checks the bearer token, proxy header or session cookie of every request against the scope it needs, requests
+/// from someone that could be identified get a [`Caller`] added to their extensions
+pub async fn authenticate<S: Store, B: MessageBody + 'static>(
+    request: ServiceRequest,
+    next: Next<B>,
+) -> Result<ServiceResponse<EitherBody<B>>, Error> {
+    match caller::<S>(&request).await {
+        Ok(caller) => {
+            if let Some(caller) = caller {
+                request.extensions_mut().insert(caller);
+            }
+            next.call(request)
+                .await
+                .map(ServiceResponse::map_into_left_body)
+        }
+        // Answered here so the response still goes through the rest of the middleware
+        Err(err) => Ok(request.error_response(err).map_into_right_body()),
+    }
+}
+
+/// Fails unless `caller` has the `required` scope, `action` is what they were trying to do.
+/// Handlers that manage tokens and users check again with this rather than only relying on the
+/// middleware
+pub fn check_scope(caller: Option<&Caller>, required: Scope, action: &str) -> HomescreenResult {
+    let caller = caller.ok_or(ServerError::Unauthorized)?;
+    if !caller.scope().allows(required) {
+        warn!(
+            "Rejected {action} from {} with the {} scope",
+            caller.name(),
+            caller.scope()
+        );
+        return Err(ServerError::Forbidden(action.to_owned(), required.to_string()).into());
+    }
+
+    Ok(())
+}
+
+/// The route `request` is going to, the router decodes percent-encoded characters before it
+/// matches anything so the raw path can't be used to decide the scope
+fn route(request: &ServiceRequest) -> String {
+    let path = request.match_info().as_str();
+
+    request
+        .request()
+        .resource_map()
+        .match_pattern(path)
+        .unwrap_or_else(|| path.to_owned())
+}
+
+/// Who sent `request`, `None` for anonymous requests that don't need a scope
+async fn caller<S: Store>(request: &ServiceRequest) -> HomescreenResult<Option<Caller>> {
+    let route = route(request);
+    let required = required_scope(request.method(), &route);
+    let store = request
+        .app_data::<Data<S>>()
+        .expect("the store should be added as app data");
+    let caller = match (
+        bearer_token(request),
+        proxied_user(request)?,
+        request.cookie(SESSION_COOKIE),
+    ) {
+        (Some(secret), _, _) => {
+            let token = store
+                .find_token(&ApiToken::hash(secret))
+                .await?
+                .ok_or(ServerError::Unauthorized)
+                .inspect_err(|_| warn!("Rejected request with an unknown api token"))?;
+
+            Some(Caller::new(token.name().to_owned(), token.scope()))
+        }
+        // The proxy has already authenticated them, but only people with a user here are let in
+        (None, Some(name), _) => {
+            let (user, _) = store
+                .find_user(name)
+                .await?
+                .ok_or(ServerError::Unauthorized)
+                .inspect_err(|_| warn!("Rejected proxied user {name} who has no user here"))?;
+
+            Some(Caller::user(user.name().to_owned(), user.role().scope()))
+        }
+        // Sessions that have expired are treated as not being logged in at all, so a stale cookie
+        // doesn't get in the way of just looking at the homescreen
+        (None, None, Some(session)) => store
+            .find_session(&ApiToken::hash(session.value()), Utc::now())
+            .await?
+            .map(|user| Caller::user(user.name().to_owned(), user.role().scope())),
+        (None, None, None) => None,
+    };
+
+    if let Some(required) = required {
+        check_scope(
+            caller.as_ref(),
+            required,
+            &format!("{} {route}", request.method()),
+        )?;
+        if let Some(caller) = &caller {
+            info!(
+                "Authenticated {} with the {} scope",
+                caller.name(),
+                caller.scope()
+            );
+        }
+    }
+
+    Ok(caller)
+}
+
+/// The name in the proxy header, which is refused unless the request came from a trusted proxy
+fn proxied_user(request: &ServiceRequest) -> HomescreenResult<Option<&str>> {
+    let Some(proxy_auth) = request.app_data::<Data<ProxyAuthConfig>>() else {
+        return Ok(None);
+    };
+    let Some(name) = request.headers().get(proxy_auth.header()) else {
+        return Ok(None);
+    };
+
+    let trusted = request
+        .peer_addr()
+        .is_some_and(|peer| proxy_auth.trusted_proxies().trusts(peer.ip()));
+    if !trusted {
+        warn!(
+            "Rejected the {} header from untrusted address {}",
+            proxy_auth.header(),
+            request
+                .peer_addr()
+                .map_or("unknown".into(), |peer| peer.ip().to_string())
+        );
+        return Err(ServerError::UntrustedProxy(proxy_auth.header().to_owned()).into());
+    }
+
+    Ok(name
+        .to_str()
+        .ok()
+        .map(str::trim)
+        .filter(|name| !name.is_empty()))
+}
+
+fn bearer_token(request: &ServiceRequest) -> Option<&str> {
+    request
+        .headers()
+        .get(AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+}
@@ -0,0 +1,85 @@
+use homescreen_data::prelude::*;
+use homescreen_errors::prelude::*;
+
+/// What the backend should do when it is started, picked from the command line arguments
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Command {
+    /// Apply any pending migrations and then run the server
+    Serve,
+    /// Apply any pending migrations and then exit
+    MigrateOnly,
+    /// Apply any pending migrations, create an api token and write its secret to stdout, then
+    /// exit
+    CreateToken { name: String, scope: Scope },
+    /// Apply any pending migrations, create a user or change their password with one read from
+    /// stdin, then exit
+    CreateUser { name: String, role: Role },
+}
+impl Command {
+    /// ```rust
+    /// # use homescreen_backend::prelude::*;
+    /// # use homescreen_data::prelude::*;
+    /// assert_eq!(Command::from_args([]).unwrap(), Command::Serve);
+    /// assert_eq!(Command::from_args(["--migrate-only".into()]).unwrap(), Command::MigrateOnly);
+    /// assert_eq!(
+    ///     Command::from_args(["--create-token".into(), "ci".into(), "--scope".into(), "write".into()]).unwrap(),
+    ///     Command::CreateToken { name: "ci".into(), scope: Scope::Write },
+    /// );
+    /// assert_eq!(
+    ///     Command::from_args(["--create-user".into(), "sam".into()]).unwrap(),
+    ///     Command::CreateUser { name: "sam".into(), role: Role::Admin },
+    /// );
+    /// assert!(Command::from_args(["--create-token".into()]).is_err());
+    /// assert!(Command::from_args(["--scope".into(), "read".into()]).is_err());
+    /// assert!(Command::from_args(["--create-token".into(), "ci".into(), "--role".into(), "viewer".into()]).is_err());
+    /// assert!(Command::from_args(["--migrate".into()]).is_err());
+    /// ```
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> HomescreenResult<Self> {
+        let mut command = Self::Serve;
+        let mut scope = None;
+        let mut role = None;
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--migrate-only" => command = Self::MigrateOnly,
+                "--create-token" => {
+                    let name = args.next().ok_or(StartupError::MissingArgumentValue(arg))?;
+                    command = Self::CreateToken {
+                        name,
+                        scope: Scope::Admin,
+                    };
+                }
+                "--scope" => {
+                    let value = args.next().ok_or(StartupError::MissingArgumentValue(arg))?;
+                    scope = Some(value.parse()?);
+                }
+                "--create-user" => {
+                    let name = args.next().ok_or(StartupError::MissingArgumentValue(arg))?;
+                    command = Self::CreateUser {
+                        name,
+                        role: Role::Admin,
+                    };
+                }
+                "--role" => {
+                    let value = args.next().ok_or(StartupError::MissingArgumentValue(arg))?;
+                    role = Some(value.parse()?);
+                }
+                _ => return Err(StartupError::UnknownArgument(arg).into()),
+            }
+        }
+
+        match (&mut command, scope) {
+            (Self::CreateToken { scope, .. }, Some(picked)) => *scope = picked,
+            (_, Some(_)) => return Err(StartupError::UnknownArgument("--scope".into()).into()),
+            _ => {}
+        }
+        match (&mut command, role) {
+            (Self::CreateUser { role, .. }, Some(picked)) => *role = picked,
+            (_, Some(_)) => return Err(StartupError::UnknownArgument("--role".into()).into()),
+            _ => {}
+        }
+
+        Ok(command)
+    }
+}
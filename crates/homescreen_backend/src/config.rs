@@ -1,15 +1,26 @@
 use std::fs;
 
-use actix_web::web::Data;
+use crate::{oidc::OidcConfig, proxy::ProxyAuthConfig};
+use homescreen_data::prelude::*;
 use homescreen_errors::prelude::*;
 use log::{debug, trace};
 use serde::{Deserialize, Serialize};
-use sqlx::MySqlPool;
 
 #[derive(Serialize, Deserialize)]
 pub struct Config {
     port: u16,
     database_url: String,
+    /// Another file that sections and websites are declared in, as well as any declared here
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sites: Option<String>,
+    /// The identity provider people can log in through, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    oidc: Option<OidcConfig>,
+    /// The reverse proxies trusted to say who is making a request, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    proxy_auth: Option<ProxyAuthConfig>,
+    #[serde(flatten)]
+    declared: Sites,
 }
 impl Config {
     pub fn load() -> HomescreenResult<Self> {
@@ -31,15 +42,36 @@ impl Config {
     pub fn database_url(&self) -> &str {
         &self.database_url
     }
-    pub async fn connect_to_database(&self) -> HomescreenResult<Data<Box<MySqlPool>>> {
-        MySqlPool::connect(&self.database_url)
-            .await
-            .map_err(|err| {
-                StartupError::CannotConnectToDatabase(err, self.database_url().to_string())
-            })
-            .inspect(|_| trace!("Connected to database"))
-            .map(Box::new)
-            .map(Data::new)
-            .map_err(HomescreenError::from)
+    pub fn oidc(&self) -> Option<&OidcConfig> {
+        self.oidc.as_ref()
+    }
+    pub fn proxy_auth(&self) -> Option<&ProxyAuthConfig> {
+        self.proxy_auth.as_ref()
+    }
+    /// The sections and websites declared in Config.toml followed by the ones in the sites file
+    pub fn sites(&self) -> HomescreenResult<Sites> {
+        let mut sites = self.declared.clone();
+
+        if let Some(path) = &self.sites {
+            debug!("Loading sites from {path}");
+
+            let declared = fs::read_to_string(path)
+                .map_err(|err| ConfigError::CannotFindSitesFile(err, path.clone()))
+                .inspect(|_| trace!("Loaded sites"))
+                .and_then(|declared| {
+                    toml::from_str(&declared)
+                        .map_err(|err| ConfigError::CannotParseSitesFile(err, path.clone()))
+                        .inspect(|_| trace!("Parsed sites"))
+                })?;
+            sites.extend(declared);
+        }
+
+        Ok(sites)
+    }
+    /// Connects to the database, the driver is picked from the scheme of the database url
+    pub async fn connect_to_database(&self) -> HomescreenResult<SqlStore> {
+        debug!("Connecting to database");
+
+        SqlStore::connect(&self.database_url).await
     }
 }
@@ -1,32 +1,96 @@
+pub mod auth;
+pub mod command;
 pub mod config;
+pub mod oidc;
 pub mod prelude;
+pub mod proxy;
 pub mod routes;
 
 use crate::prelude::*;
 use actix_cors::Cors;
-use actix_web::{web::Data, App, HttpServer};
+use actix_web::{middleware::from_fn, web::Data, App, HttpServer};
+use homescreen_data::prelude::*;
 use homescreen_errors::prelude::*;
 use log::info;
+use std::io::{self, Write};
 
-pub async fn try_main() -> HomescreenResult {
+pub async fn try_main(command: Command) -> HomescreenResult {
     let config = Config::load()?;
-    let database = config.connect_to_database().await?;
+    let store = config.connect_to_database().await?;
+
+    store.migrate().await?;
+    match command {
+        Command::Serve => {}
+        Command::MigrateOnly => {
+            info!("Database is up to date");
+            return Ok(());
+        }
+        Command::CreateToken { name, scope } => {
+            let (token, secret) = auth::issue_token(&store, name, scope).await?;
+            info!("Created api token {} with the {scope} scope", token.name());
+            // Written to stdout rather than logged so it isn't left behind in the logs
+            writeln!(io::stdout().lock(), "{secret}").map_err(StartupError::CannotWriteToken)?;
+            return Ok(());
+        }
+        Command::CreateUser { name, role } => {
+            let mut password = String::new();
+            io::stdin()
+                .read_line(&mut password)
+                .map_err(StartupError::CannotReadPassword)?;
+            let password = password.trim_end_matches(['\r', '\n']);
+
+            let created = auth::save_user(&store, name.clone(), password, role).await?;
+            if created {
+                info!("Created {name} with the {role} role");
+            } else {
+                info!("Changed the password and role of {name}");
+            }
+            return Ok(());
+        }
+    }
+
+    store.sync_sites(config.sites()?).await?;
+
+    let oidc = match config.oidc() {
+        Some(oidc) => Some(OidcProvider::discover(oidc.clone()).await?),
+        None => None,
+    };
+
+    serve(store, config.port(), oidc, config.proxy_auth().cloned()).await
+}
+
+/// Runs the backend on `port` until it is stopped, using `store` for everything that needs to be
+/// persisted, letting people log in through `oidc` and trusting the proxies in `proxy_auth` if
+/// they're given
+pub async fn serve<S: Store>(
+    store: S,
+    port: u16,
+    oidc: Option<OidcProvider>,
+    proxy_auth: Option<ProxyAuthConfig>,
+) -> HomescreenResult {
+    let store = Data::new(store);
+    let oidc = oidc.map(Data::new);
+    let proxy_auth = proxy_auth.map(Data::new);
 
     HttpServer::new(move || {
         let cors = Cors::default().allow_any_origin();
-        App::new()
-            .service(websites::get_websites)
-            .service(websites::get_fun_website)
-            .service(websites::get_coding_websites)
-            .service(websites::get_editing_websites)
-            .service(websites::create_or_update_website)
-            .service(websites::delete_website)
+        let app = App::new()
+            .configure(configure::<S>)
+            .wrap(from_fn(auth::authenticate::<S, _>))
             .wrap(cors)
-            .app_data(Data::clone(&database))
+            .app_data(Data::clone(&store));
+        let app = match &oidc {
+            Some(oidc) => app.app_data(Data::clone(oidc)),
+            None => app,
+        };
+        match &proxy_auth {
+            Some(proxy_auth) => app.app_data(Data::clone(proxy_auth)),
+            None => app,
+        }
     })
-    .bind(("127.0.0.1", config.port()))
-    .map_err(|err| StartupError::CannotBindToPort(err, config.port()))
-    .inspect(|_| info!("Starting server on port {}", config.port()))?
+    .bind(("127.0.0.1", port))
+    .map_err(|err| StartupError::CannotBindToPort(err, port))
+    .inspect(|_| info!("Starting server on port {port}"))?
     .run()
     .await
     .map_err(StartupError::CannotStartServer)?;
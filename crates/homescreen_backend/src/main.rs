@@ -1,11 +1,18 @@
-use homescreen_backend::try_main;
+use homescreen_backend::{prelude::*, try_main};
 use log::error;
+use std::{env, process};
 
 #[actix_web::main]
 async fn main() {
     env_logger::init();
 
-    if let Err(err) = try_main().await {
+    let result = match Command::from_args(env::args().skip(1)) {
+        Ok(command) => try_main(command).await,
+        Err(err) => Err(err),
+    };
+
+    if let Err(err) = result {
         error!("{err}");
+        process::exit(1);
     }
 }
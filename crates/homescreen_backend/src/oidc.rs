@@ -0,0 +1,324 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use homescreen_data::prelude::*;
+use homescreen_errors::prelude::*;
+use log::{debug, info, warn};
+use openidconnect::{
+    core::{
+        CoreAuthDisplay, CoreAuthPrompt, CoreAuthenticationFlow, CoreErrorResponseType,
+        CoreGenderClaim, CoreJsonWebKey, CoreJweContentEncryptionAlgorithm,
+        CoreJwsSigningAlgorithm, CoreProviderMetadata, CoreRevocableToken,
+        CoreRevocationErrorResponse, CoreTokenIntrospectionResponse, CoreTokenType,
+    },
+    reqwest, AdditionalClaims, AuthorizationCode, Client, ClientId, ClientSecret, CsrfToken,
+    EmptyExtraTokenFields, EndpointMaybeSet, EndpointNotSet, EndpointSet, IdTokenClaims,
+    IdTokenFields, IssuerUrl, Nonce, PkceCodeChallenge, PkceCodeVerifier, RedirectUrl,
+    StandardErrorResponse, StandardTokenResponse, TokenResponse,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{collections::HashMap, sync::Mutex};
+
+/// How long someone has to log in with the identity provider before they have to start again
+pub(crate) const LOGIN_TIMEOUT: TimeDelta = TimeDelta::minutes(10);
+/// How many logins can be waiting on the identity provider at once, anyone can start one so the
+/// oldest are forgotten past this
+const MAX_PENDING_LOGINS: usize = 1024;
+
+/// Logging in through an OpenID Connect identity provider, the `[oidc]` table in Config.toml
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct OidcConfig {
+    issuer: String,
+    client_id: String,
+    client_secret: String,
+    /// Where the identity provider sends people back to, the `/oidc/callback` route as the
+    /// browser sees it
+    redirect_url: String,
+    /// Shown on the button for logging in
+    #[serde(default = "OidcConfig::default_name")]
+    name: String,
+    #[serde(default = "OidcConfig::default_scopes")]
+    scopes: Vec<String>,
+    /// The claim the name of a new homescreen user is taken from, they are matched on their
+    /// subject after that
+    #[serde(default = "OidcConfig::default_username_claim")]
+    username_claim: String,
+    /// The claim listing the groups or roles someone has at the identity provider
+    #[serde(default = "OidcConfig::default_roles_claim")]
+    roles_claim: String,
+    /// Anyone with one of these in their roles claim is an admin, everyone else is a viewer
+    #[serde(default)]
+    admin_roles: Vec<String>,
+}
+impl OidcConfig {
+    pub fn new(
+        issuer: String,
+        client_id: String,
+        client_secret: String,
+        redirect_url: String,
+    ) -> Self {
+        Self {
+            issuer,
+            client_id,
+            client_secret,
+            redirect_url,
+            name: Self::default_name(),
+            scopes: Self::default_scopes(),
+            username_claim: Self::default_username_claim(),
+            roles_claim: Self::default_roles_claim(),
+            admin_roles: Vec::new(),
+        }
+    }
+    pub fn with_admin_roles(mut self, admin_roles: Vec<String>) -> Self {
+        self.admin_roles = admin_roles;
+        self
+    }
+    fn default_name() -> String {
+        "single sign-on".into()
+    }
+    fn default_scopes() -> Vec<String> {
+        vec!["profile".into(), "email".into()]
+    }
+    fn default_username_claim() -> String {
+        "preferred_username".into()
+    }
+    fn default_roles_claim() -> String {
+        "groups".into()
+    }
+    pub fn issuer(&self) -> &str {
+        &self.issuer
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// The role someone gets from the roles they have at the identity provider
+    ///
+    /// ```rust
+    /// # use homescreen_backend::prelude::*;
+    /// # use homescreen_data::prelude::*;
+    /// let config = OidcConfig::new(
+    ///     "https://id.example.com".into(),
+    ///     "homescreen".into(),
+    ///     "secret".into(),
+    ///     "https://home.example.com/oidc/callback".into(),
+    /// )
+    /// .with_admin_roles(vec!["homescreen-admins".into()]);
+    /// assert_eq!(config.role(&["staff".into(), "homescreen-admins".into()]), Role::Admin);
+    /// assert_eq!(config.role(&["staff".into()]), Role::Viewer);
+    /// ```
+    pub fn role(&self, roles: &[String]) -> Role {
+        if roles.iter().any(|role| self.admin_roles.contains(role)) {
+            Role::Admin
+        } else {
+            Role::Viewer
+        }
+    }
+}
+
+/// Every claim in an ID token that isn't one of the standard ones, such as groups
+#[derive(Serialize, Deserialize, Clone, Default, Debug)]
+pub struct OtherClaims {
+    #[serde(flatten)]
+    claims: HashMap<String, Value>,
+}
+impl AdditionalClaims for OtherClaims {}
+
+type OidcTokenResponse = StandardTokenResponse<
+    IdTokenFields<
+        OtherClaims,
+        EmptyExtraTokenFields,
+        CoreGenderClaim,
+        CoreJweContentEncryptionAlgorithm,
+        CoreJwsSigningAlgorithm,
+    >,
+    CoreTokenType,
+>;
+type OidcClient = Client<
+    OtherClaims,
+    CoreAuthDisplay,
+    CoreGenderClaim,
+    CoreJweContentEncryptionAlgorithm,
+    CoreJsonWebKey,
+    CoreAuthPrompt,
+    StandardErrorResponse<CoreErrorResponseType>,
+    OidcTokenResponse,
+    CoreTokenIntrospectionResponse,
+    CoreRevocableToken,
+    CoreRevocationErrorResponse,
+    EndpointSet,
+    EndpointNotSet,
+    EndpointNotSet,
+    EndpointNotSet,
+    EndpointMaybeSet,
+    EndpointMaybeSet,
+>;
+
+/// A login that has been sent to the identity provider but hasn't come back yet
+struct PendingLogin {
+    nonce: Nonce,
+    verifier: PkceCodeVerifier,
+    started_at: DateTime<Utc>,
+}
+
+/// An identity provider found through OpenID Connect discovery, added as app data when one is
+/// configured
+pub struct OidcProvider {
+    config: OidcConfig,
+    client: OidcClient,
+    http: reqwest::Client,
+    /// Keyed by the state sent to the identity provider, which it sends back unchanged
+    pending: Mutex<HashMap<String, PendingLogin>>,
+}
+impl OidcProvider {
+    /// Fetches the provider's metadata and signing keys from the issuer, the keys aren't fetched
+    /// again so the backend has to be restarted if the provider replaces them
+    pub async fn discover(config: OidcConfig) -> HomescreenResult<Self> {
+        debug!("Discovering identity provider {}", config.issuer);
+
+        let discovery_error =
+            |why: String| StartupError::CannotDiscoverOidcProvider(config.issuer.clone(), why);
+        // Following redirects would let the provider send the backend's requests anywhere
+        let http = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .map_err(|err| discovery_error(err.to_string()))?;
+        let issuer = IssuerUrl::new(config.issuer.clone())
+            .map_err(|err| discovery_error(err.to_string()))?;
+        let redirect_url = RedirectUrl::new(config.redirect_url.clone())
+            .map_err(|err| discovery_error(err.to_string()))?;
+        let metadata = CoreProviderMetadata::discover_async(issuer, &http)
+            .await
+            .map_err(|err| discovery_error(err.to_string()))?;
+
+        let client = OidcClient::from_provider_metadata(
+            metadata,
+            ClientId::new(config.client_id.clone()),
+            Some(ClientSecret::new(config.client_secret.clone())),
+        )
+        .set_redirect_uri(redirect_url);
+
+        info!("Discovered identity provider {}", config.issuer);
+        Ok(Self {
+            config,
+            client,
+            http,
+            pending: Mutex::new(HashMap::new()),
+        })
+    }
+    pub fn config(&self) -> &OidcConfig {
+        &self.config
+    }
+    fn pending(&self) -> std::sync::MutexGuard<'_, HashMap<String, PendingLogin>> {
+        self.pending
+            .lock()
+            .expect("pending logins should never be left poisoned")
+    }
+    /// Starts logging someone in, returns the url of the identity provider to send them to and the
+    /// state it will send back, which has to be kept by the browser
+    pub fn start_login(&self) -> (String, String) {
+        let (challenge, verifier) = PkceCodeChallenge::new_random_sha256();
+        let mut request = self.client.authorize_url(
+            CoreAuthenticationFlow::AuthorizationCode,
+            CsrfToken::new_random,
+            Nonce::new_random,
+        );
+        for scope in &self.config.scopes {
+            request = request.add_scope(openidconnect::Scope::new(scope.clone()));
+        }
+        let (url, state, nonce) = request.set_pkce_challenge(challenge).url();
+
+        let now = Utc::now();
+        let mut pending = self.pending();
+        // Logins that were never finished are cleared out as new ones start
+        pending.retain(|_, login| now - login.started_at < LOGIN_TIMEOUT);
+        while pending.len() >= MAX_PENDING_LOGINS {
+            let Some(oldest) = pending
+                .iter()
+                .min_by_key(|(_, login)| login.started_at)
+                .map(|(state, _)| state.clone())
+            else {
+                break;
+            };
+            pending.remove(&oldest);
+        }
+        pending.insert(
+            state.secret().clone(),
+            PendingLogin {
+                nonce,
+                verifier,
+                started_at: now,
+            },
+        );
+
+        (url.to_string(), state.into_secret())
+    }
+    /// Finishes logging someone in once the identity provider has sent them back with `code` and
+    /// `state`, returns the subject of their account there along with the name and role they
+    /// should have
+    pub async fn finish_login(
+        &self,
+        code: String,
+        state: &str,
+    ) -> HomescreenResult<(String, String, Role)> {
+        let failed = |why: String| HomescreenError::from(ServerError::OidcLoginFailed(why));
+
+        let login = self
+            .pending()
+            .remove(state)
+            .filter(|login| Utc::now() - login.started_at < LOGIN_TIMEOUT)
+            .ok_or_else(|| failed("the login is unknown or took too long".into()))?;
+
+        let response = self
+            .client
+            .exchange_code(AuthorizationCode::new(code))
+            .map_err(|err| failed(err.to_string()))?
+            .set_pkce_verifier(login.verifier)
+            .request_async(&self.http)
+            .await
+            .map_err(|err| failed(err.to_string()))?;
+        let claims = response
+            .id_token()
+            .ok_or_else(|| failed("the identity provider didn't send an ID token".into()))?
+            .claims(&self.client.id_token_verifier(), &login.nonce)
+            .map_err(|err| failed(err.to_string()))?;
+
+        let name = claim_values(claims, &self.config.username_claim)
+            .into_iter()
+            .next()
+            .ok_or_else(|| {
+                failed(format!(
+                    "the ID token has no {}",
+                    self.config.username_claim
+                ))
+            })
+            .inspect_err(|err| warn!("{err}"))?;
+        let role = self
+            .config
+            .role(&claim_values(claims, &self.config.roles_claim));
+
+        Ok((claims.subject().to_string(), name, role))
+    }
+}
+
+/// Every value of `claim`, which can be a single string or a list of them
+fn claim_values(claims: &IdTokenClaims<OtherClaims, CoreGenderClaim>, claim: &str) -> Vec<String> {
+    // The standard claims are parsed into their own fields rather than being left with the rest
+    let standard = match claim {
+        "sub" => Some(claims.subject().as_str()),
+        "preferred_username" => claims.preferred_username().map(|name| name.as_str()),
+        "email" => claims.email().map(|email| email.as_str()),
+        _ => None,
+    };
+    if let Some(value) = standard {
+        return vec![value.to_owned()];
+    }
+
+    match claims.additional_claims().claims.get(claim) {
+        Some(Value::String(value)) => vec![value.clone()],
+        Some(Value::Array(values)) => values
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_owned)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
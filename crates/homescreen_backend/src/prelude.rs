@@ -1 +1,11 @@
-pub use crate::{config::Config, routes::websites};
+pub use crate::{
+    auth,
+    command::Command,
+    config::Config,
+    oidc::{OidcConfig, OidcProvider},
+    proxy::ProxyAuthConfig,
+    routes::{
+        configure, export, go, import, oidc, personal, search_engines, sections, session,
+        suggestions, tokens, users, websites,
+    },
+};
@@ -0,0 +1,31 @@
+use homescreen_data::prelude::TrustedProxies;
+use serde::{Deserialize, Serialize};
+
+/// Trusting a reverse proxy that has already authenticated people, the `[proxy_auth]` table in
+/// Config.toml
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ProxyAuthConfig {
+    /// The header the proxy puts the name of whoever it authenticated in
+    #[serde(default = "ProxyAuthConfig::default_header")]
+    header: String,
+    /// The address ranges the proxy connects from, the header is refused from anywhere else
+    trusted_proxies: TrustedProxies,
+}
+impl ProxyAuthConfig {
+    pub fn new(header: String, trusted_proxies: TrustedProxies) -> Self {
+        Self {
+            header,
+            trusted_proxies,
+        }
+    }
+    fn default_header() -> String {
+        "Remote-User".into()
+    }
+    pub fn header(&self) -> &str {
+        &self.header
+    }
+    /// Where requests can say who they are with the header from
+    pub fn trusted_proxies(&self) -> &TrustedProxies {
+        &self.trusted_proxies
+    }
+}
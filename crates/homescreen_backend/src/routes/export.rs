@@ -0,0 +1,74 @@
+use actix_web::{
+    http::header::{ContentDisposition, ContentType},
+    web::{Data, Query},
+    HttpResponse,
+};
+use homescreen_data::prelude::*;
+use homescreen_errors::prelude::*;
+use log::{error, info};
+use serde::Deserialize;
+
+/// The formats backups can be written in
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum BackupFormat {
+    #[default]
+    Json,
+    Toml,
+}
+
+#[derive(Deserialize)]
+pub struct ExportQuery {
+    #[serde(default)]
+    format: BackupFormat,
+}
+
+pub async fn export<S: Store>(
+    Query(ExportQuery { format }): Query<ExportQuery>,
+    store: Data<S>,
+) -> HomescreenResult<HttpResponse> {
+    info!("Exporting backup");
+
+    let websites = store
+        .get_websites()
+        .await
+        .inspect_err(|err| error!("Unable to get websites, {err}"))?;
+    let search_engines = store
+        .get_search_engines()
+        .await
+        .inspect_err(|err| error!("Unable to get search engines, {err}"))?;
+    let backup = Backup::new(&websites, &search_engines);
+
+    let response = match format {
+        BackupFormat::Json => HttpResponse::Ok()
+            .insert_header(ContentDisposition::attachment("homescreen.json"))
+            .json(backup),
+        BackupFormat::Toml => {
+            let toml = toml::to_string(&backup)
+                .map_err(ServerError::CannotSerializeBackup)
+                .map_err(HomescreenError::from)
+                .inspect_err(|err| error!("Unable to write backup, {err}"))?;
+
+            HttpResponse::Ok()
+                .content_type("application/toml")
+                .insert_header(ContentDisposition::attachment("homescreen.toml"))
+                .body(toml)
+        }
+    };
+
+    Ok(response)
+}
+
+pub async fn export_netscape<S: Store>(store: Data<S>) -> HomescreenResult<HttpResponse> {
+    info!("Exporting netscape bookmarks");
+
+    let websites = store
+        .get_websites()
+        .await
+        .inspect_err(|err| error!("Unable to get websites, {err}"))?;
+
+    Ok(HttpResponse::Ok()
+        .content_type(ContentType::html())
+        .insert_header(ContentDisposition::attachment("bookmarks.html"))
+        .body(Bookmark::write_netscape(&websites)))
+}
@@ -0,0 +1,79 @@
+use actix_web::{
+    http::header::LOCATION,
+    web::{Data, Path, ReqData},
+    HttpResponse,
+};
+use chrono::Utc;
+use homescreen_data::prelude::*;
+use homescreen_errors::prelude::*;
+use log::{error, info};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct GoPath {
+    /// The name of the website or its alias
+    website_name: String,
+    /// Everything after the name, each segment fills in a placeholder of the link template
+    #[serde(default)]
+    arguments: String,
+}
+
+/// Counts a visit to the website and sends the browser on to it, or to its link template filled
+/// in with the arguments when there are any. Personal websites of whoever is logged in can be
+/// gone to as well but their visits aren't counted
+pub async fn go<S: Store>(
+    path: Path<GoPath>,
+    caller: Option<ReqData<Caller>>,
+    store: Data<S>,
+) -> HomescreenResult<HttpResponse> {
+    info!("Visiting website");
+
+    let GoPath {
+        website_name,
+        arguments,
+    } = path.into_inner();
+
+    let website = match store.record_visit(&website_name, Utc::now()).await {
+        Err(HomescreenError::Server(ServerError::CannotFindWebsite)) => {
+            personal_website(&website_name, caller, store.get_ref()).await?
+        }
+        visited => {
+            visited.inspect_err(|err| error!("Unable to record visit to {website_name}, {err}"))?
+        }
+    };
+
+    let arguments: Vec<&str> = arguments
+        .split('/')
+        .filter(|argument| !argument.is_empty())
+        .collect();
+    let location = match arguments.is_empty() {
+        true => None,
+        false => website.expand(&arguments),
+    }
+    .unwrap_or_else(|| website.url());
+
+    Ok(HttpResponse::Found()
+        .insert_header((LOCATION, location))
+        .finish())
+}
+
+/// The personal website of the caller called `website_name`, for when there's no shared one
+async fn personal_website<S: Store>(
+    website_name: &str,
+    caller: Option<ReqData<Caller>>,
+    store: &S,
+) -> HomescreenResult<Website> {
+    let Some(caller) = caller.filter(|caller| caller.is_user()) else {
+        error!("Unable to record visit to {website_name}, it doesn't exist");
+        return Err(ServerError::CannotFindWebsite.into());
+    };
+
+    store
+        .get_overlay(caller.name())
+        .await?
+        .website(website_name)
+        .cloned()
+        .ok_or(ServerError::CannotFindWebsite)
+        .map_err(HomescreenError::from)
+        .inspect_err(|err| error!("Unable to go to {website_name}, {err}"))
+}
@@ -0,0 +1,108 @@
+use super::export::BackupFormat;
+use actix_web::{
+    web::{Bytes, Data, Query},
+    HttpResponse,
+};
+use homescreen_data::prelude::*;
+use homescreen_errors::prelude::*;
+use log::{error, info};
+use serde::Deserialize;
+
+/// Bookmark files are a lot bigger than any form, so imports get a higher limit than the default
+pub const IMPORT_LIMIT: usize = 16 * 1024 * 1024;
+
+/// Firefox keeps the browsing history in the same database as the bookmarks, so it can be a lot
+/// bigger than a bookmark file. History databases get the same limit
+pub const BROWSER_DATABASE_LIMIT: usize = 256 * 1024 * 1024;
+
+#[derive(Deserialize)]
+pub struct ImportQuery {
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[derive(Deserialize)]
+pub struct BackupQuery {
+    #[serde(default)]
+    format: BackupFormat,
+    #[serde(default)]
+    strategy: ImportStrategy,
+}
+
+pub async fn import<S: Store>(
+    Query(BackupQuery { format, strategy }): Query<BackupQuery>,
+    document: String,
+    store: Data<S>,
+) -> HomescreenResult<HttpResponse> {
+    info!("Importing backup");
+
+    let backup: Backup = match format {
+        BackupFormat::Json => serde_json::from_str(&document).map_err(|err| err.to_string()),
+        BackupFormat::Toml => toml::from_str(&document).map_err(|err| err.to_string()),
+    }
+    .map_err(ServerError::CannotParseBackup)
+    .map_err(HomescreenError::from)
+    .inspect_err(|err| error!("Unable to parse backup, {err}"))?;
+
+    store
+        .import_backup(backup, strategy)
+        .await
+        .inspect_err(|err| error!("Unable to import backup, {err}"))?;
+
+    let websites = store
+        .get_websites()
+        .await
+        .inspect_err(|err| error!("Unable to get websites, {err}"))?;
+
+    Ok(HttpResponse::Ok().json(websites))
+}
+
+pub async fn import_netscape<S: Store>(
+    Query(ImportQuery { dry_run }): Query<ImportQuery>,
+    html: String,
+    store: Data<S>,
+) -> HomescreenResult<HttpResponse> {
+    info!("Importing netscape bookmarks");
+
+    let report = store
+        .import_bookmarks(Bookmark::parse_netscape(&html), dry_run)
+        .await
+        .inspect_err(|err| error!("Unable to import bookmarks, {err}"))?;
+
+    Ok(HttpResponse::Ok().json(report))
+}
+
+pub async fn import_firefox<S: Store>(
+    Query(ImportQuery { dry_run }): Query<ImportQuery>,
+    places: Bytes,
+    store: Data<S>,
+) -> HomescreenResult<HttpResponse> {
+    info!("Importing firefox bookmarks");
+
+    let bookmarks = Bookmark::read_firefox(&places)
+        .await
+        .inspect_err(|err| error!("Unable to read firefox bookmarks, {err}"))?;
+    let report = store
+        .import_bookmarks(bookmarks, dry_run)
+        .await
+        .inspect_err(|err| error!("Unable to import bookmarks, {err}"))?;
+
+    Ok(HttpResponse::Ok().json(report))
+}
+
+pub async fn import_chromium<S: Store>(
+    Query(ImportQuery { dry_run }): Query<ImportQuery>,
+    json: String,
+    store: Data<S>,
+) -> HomescreenResult<HttpResponse> {
+    info!("Importing chromium bookmarks");
+
+    let bookmarks = Bookmark::parse_chromium(&json)
+        .inspect_err(|err| error!("Unable to parse chromium bookmarks, {err}"))?;
+    let report = store
+        .import_bookmarks(bookmarks, dry_run)
+        .await
+        .inspect_err(|err| error!("Unable to import bookmarks, {err}"))?;
+
+    Ok(HttpResponse::Ok().json(report))
+}
@@ -1 +1,119 @@
+pub mod export;
+pub mod go;
+pub mod import;
+pub mod oidc;
+pub mod personal;
+pub mod search_engines;
+pub mod sections;
+pub mod session;
+pub mod suggestions;
+pub mod tokens;
+pub mod users;
 pub mod websites;
+
+use actix_web::web::{self, PayloadConfig, ServiceConfig};
+use homescreen_data::prelude::Store;
+
+/// Registers every route against the store `S`, the store itself has to be added as app data
+/// separately
+pub fn configure<S: Store>(config: &mut ServiceConfig) {
+    config
+        .service(
+            web::resource("/websites")
+                .get(websites::get_websites::<S>)
+                .post(websites::create_website::<S>)
+                .put(websites::create_or_update_website::<S>),
+        )
+        // Has to come before `/websites/{website_name}` or that would match it instead
+        .service(web::resource("/websites/reorder").post(websites::reorder_websites::<S>))
+        .service(web::resource("/websites/{website_name}/stats").get(websites::website_stats::<S>))
+        .service(
+            web::resource("/websites/{website_name}")
+                .patch(websites::update_website::<S>)
+                .delete(websites::delete_website::<S>),
+        )
+        .service(
+            web::resource("/sections")
+                .get(sections::get_sections::<S>)
+                .put(sections::create_section::<S>),
+        )
+        .service(
+            web::resource("/sections/{slug}")
+                .patch(sections::update_section::<S>)
+                .delete(sections::delete_section::<S>),
+        )
+        .service(
+            web::resource("/sections/{slug}/websites").get(sections::get_section_websites::<S>),
+        )
+        .service(
+            web::resource("/search-engines")
+                .get(search_engines::get_search_engines::<S>)
+                .put(search_engines::create_or_update_search_engine::<S>),
+        )
+        .service(
+            web::resource("/search-engines/{name}")
+                .delete(search_engines::delete_search_engine::<S>),
+        )
+        .service(
+            web::resource("/tokens")
+                .get(tokens::get_tokens::<S>)
+                .put(tokens::create_token::<S>),
+        )
+        .service(web::resource("/tokens/{name}").delete(tokens::delete_token::<S>))
+        .service(
+            web::resource("/users")
+                .get(users::get_users::<S>)
+                .put(users::create_or_update_user::<S>),
+        )
+        .service(web::resource("/users/{name}").delete(users::delete_user::<S>))
+        .service(
+            web::resource("/personal/websites")
+                .put(personal::create_or_update_personal_website::<S>),
+        )
+        .service(
+            web::resource("/personal/websites/{website_name}")
+                .delete(personal::delete_personal_website::<S>),
+        )
+        .service(web::resource("/personal/hidden").get(personal::get_hidden_websites::<S>))
+        .service(
+            web::resource("/personal/hidden/{website_name}")
+                .put(personal::hide_website::<S>)
+                .delete(personal::show_website::<S>),
+        )
+        .service(web::resource("/personal/reorder").post(personal::reorder_personal_websites::<S>))
+        .service(web::resource("/login").post(session::log_in::<S>))
+        .service(web::resource("/logout").post(session::log_out::<S>))
+        .service(web::resource("/session").get(session::session))
+        .service(web::resource("/oidc").get(oidc::single_sign_on))
+        .service(web::resource("/oidc/login").get(oidc::start_login))
+        .service(web::resource("/oidc/callback").get(oidc::finish_login::<S>))
+        .service(web::resource("/go/{website_name}").get(go::go::<S>))
+        .service(web::resource("/go/{website_name}/{arguments:.*}").get(go::go::<S>))
+        .service(web::resource("/export").get(export::export::<S>))
+        .service(web::resource("/export/netscape").get(export::export_netscape::<S>))
+        .service(
+            web::resource("/import")
+                .app_data(PayloadConfig::new(import::IMPORT_LIMIT))
+                .post(import::import::<S>),
+        )
+        .service(
+            web::resource("/import/netscape")
+                .app_data(PayloadConfig::new(import::IMPORT_LIMIT))
+                .post(import::import_netscape::<S>),
+        )
+        .service(
+            web::resource("/import/firefox")
+                .app_data(PayloadConfig::new(import::BROWSER_DATABASE_LIMIT))
+                .post(import::import_firefox::<S>),
+        )
+        .service(
+            web::resource("/import/chromium")
+                .app_data(PayloadConfig::new(import::IMPORT_LIMIT))
+                .post(import::import_chromium::<S>),
+        )
+        .service(
+            web::resource("/suggestions")
+                .app_data(PayloadConfig::new(import::BROWSER_DATABASE_LIMIT))
+                .post(suggestions::suggest_websites::<S>),
+        );
+}
@@ -0,0 +1,121 @@
+use crate::{
+    auth::SESSION_LENGTH,
+    oidc::{OidcProvider, LOGIN_TIMEOUT},
+    routes::session::session_cookie,
+};
+use actix_web::{
+    cookie::{time::Duration, Cookie, SameSite},
+    http::header::LOCATION,
+    web::{Data, Query},
+    HttpRequest, HttpResponse,
+};
+use chrono::Utc;
+use homescreen_data::prelude::*;
+use homescreen_errors::prelude::*;
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+
+/// The cookie holding the state of the login a browser started, so a callback only finishes a
+/// login in the browser that started it
+pub const STATE_COOKIE: &str = "homescreen_oidc_state";
+
+/// What the identity provider sends people back with
+#[derive(Deserialize)]
+pub struct Callback {
+    code: String,
+    state: String,
+}
+
+/// The identity provider, so the frontend knows whether to show a button for it
+#[derive(Serialize)]
+pub struct SingleSignOn {
+    name: String,
+}
+
+/// The state of the login this browser started, only sent back to the callback and only when the
+/// identity provider links back here
+fn state_cookie(value: String) -> Cookie<'static> {
+    Cookie::build(STATE_COOKIE, value)
+        .path("/oidc")
+        .http_only(true)
+        .secure(true)
+        .same_site(SameSite::Lax)
+        .max_age(Duration::seconds(LOGIN_TIMEOUT.num_seconds()))
+        .finish()
+}
+
+fn provider(oidc: Option<Data<OidcProvider>>) -> HomescreenResult<Data<OidcProvider>> {
+    oidc.ok_or(ServerError::OidcNotConfigured)
+        .map_err(HomescreenError::from)
+}
+
+pub async fn single_sign_on(oidc: Option<Data<OidcProvider>>) -> HomescreenResult<HttpResponse> {
+    info!("Retrieving single sign-on");
+
+    let oidc = provider(oidc)?;
+    Ok(HttpResponse::Ok().json(SingleSignOn {
+        name: oidc.config().name().to_owned(),
+    }))
+}
+
+pub async fn start_login(oidc: Option<Data<OidcProvider>>) -> HomescreenResult<HttpResponse> {
+    info!("Starting single sign-on");
+
+    let oidc = provider(oidc)?;
+    let (url, state) = oidc.start_login();
+    Ok(HttpResponse::Found()
+        .cookie(state_cookie(state))
+        .insert_header((LOCATION, url))
+        .finish())
+}
+
+pub async fn finish_login<S: Store>(
+    Query(Callback { code, state }): Query<Callback>,
+    request: HttpRequest,
+    oidc: Option<Data<OidcProvider>>,
+    store: Data<S>,
+) -> HomescreenResult<HttpResponse> {
+    info!("Finishing single sign-on");
+
+    let oidc = provider(oidc)?;
+    // Otherwise someone could send their own callback link to someone else and have them logged
+    // in as the sender
+    if request
+        .cookie(STATE_COOKIE)
+        .is_none_or(|cookie| cookie.value() != state)
+    {
+        warn!("Rejected single sign-on started in another browser");
+        return Err(ServerError::OidcLoginFailed(
+            "the login was started in another browser".into(),
+        )
+        .into());
+    }
+    let (subject, name, role) = oidc
+        .finish_login(code, &state)
+        .await
+        .inspect_err(|err| warn!("Rejected single sign-on, {err}"))?;
+
+    let user = store
+        .create_or_update_external_user(
+            User::new(name.clone(), role, Utc::now()),
+            oidc.config().issuer(),
+            &subject,
+        )
+        .await
+        .inspect_err(|err| error!("Unable to save {name} from the identity provider, {err}"))?;
+    let name = user.name();
+
+    let secret = User::new_session();
+    store
+        .create_session(ApiToken::hash(&secret), name, Utc::now() + SESSION_LENGTH)
+        .await
+        .inspect_err(|err| error!("Unable to create session for {name}, {err}"))?;
+
+    let mut finished = state_cookie(String::new());
+    finished.make_removal();
+    Ok(HttpResponse::Found()
+        .cookie(session_cookie(secret))
+        .cookie(finished)
+        .insert_header((LOCATION, "/"))
+        .finish())
+}
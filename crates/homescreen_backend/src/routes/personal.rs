@@ -0,0 +1,156 @@
+use actix_web::{
+    web::{Data, Form, Json, Path, ReqData},
+    HttpResponse,
+};
+use homescreen_data::prelude::*;
+use homescreen_errors::prelude::*;
+use log::{error, info, warn};
+use serde::Deserialize;
+
+/// Personal websites only have a name, link and section, everything else belongs to the shared
+/// homescreen
+#[derive(Deserialize)]
+pub struct PersonalWebsiteForm {
+    website_name: String,
+    website_link: String,
+    section: String,
+}
+
+/// The name of the user making the request, api tokens don't have a personal homescreen
+fn owner(caller: Option<ReqData<Caller>>) -> HomescreenResult<String> {
+    match caller {
+        Some(caller) if caller.is_user() => Ok(caller.name().to_owned()),
+        Some(caller) => {
+            warn!(
+                "Rejected personal homescreen request from {}",
+                caller.name()
+            );
+            Err(ServerError::NoPersonalHomescreen.into())
+        }
+        None => Err(ServerError::Unauthorized.into()),
+    }
+}
+
+pub async fn create_or_update_personal_website<S: Store>(
+    Form(PersonalWebsiteForm {
+        website_name,
+        website_link,
+        section,
+    }): Form<PersonalWebsiteForm>,
+    caller: Option<ReqData<Caller>>,
+    store: Data<S>,
+) -> HomescreenResult<HttpResponse> {
+    info!("Inserting or updating personal website");
+
+    let owner = owner(caller)?;
+    let website = Website::new(website_name, website_link, section)
+        .inspect_err(|err| error!("Unable to parse personal website from form, {err}"))?;
+
+    match store
+        .create_or_update_personal_website(&owner, website)
+        .await
+        .inspect_err(|err| error!("Unable to save personal website of {owner}, {err}"))?
+    {
+        WebsiteChange::Created => Ok(HttpResponse::Created().finish()),
+        WebsiteChange::Updated => Ok(HttpResponse::Ok().finish()),
+    }
+}
+
+pub async fn delete_personal_website<S: Store>(
+    website_name: Path<String>,
+    caller: Option<ReqData<Caller>>,
+    store: Data<S>,
+) -> HomescreenResult<HttpResponse> {
+    info!("Deleting personal website");
+
+    let owner = owner(caller)?;
+    store
+        .delete_personal_website(&owner, website_name.as_str())
+        .await
+        .inspect_err(|err| {
+            error!(
+                "Unable to delete personal website {} of {owner}, {err}",
+                website_name.as_str()
+            )
+        })?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+pub async fn get_hidden_websites<S: Store>(
+    caller: Option<ReqData<Caller>>,
+    store: Data<S>,
+) -> HomescreenResult<HttpResponse> {
+    info!("Retrieving hidden websites");
+
+    let owner = owner(caller)?;
+    let overlay = store
+        .get_overlay(&owner)
+        .await
+        .inspect_err(|err| error!("Unable to get personal homescreen of {owner}, {err}"))?;
+
+    Ok(HttpResponse::Ok().json(overlay.hidden()))
+}
+
+pub async fn hide_website<S: Store>(
+    website_name: Path<String>,
+    caller: Option<ReqData<Caller>>,
+    store: Data<S>,
+) -> HomescreenResult<HttpResponse> {
+    info!("Hiding website");
+
+    let owner = owner(caller)?;
+    store
+        .set_website_hidden(&owner, website_name.as_str(), true)
+        .await
+        .inspect_err(|err| {
+            error!(
+                "Unable to hide {} from {owner}, {err}",
+                website_name.as_str()
+            )
+        })?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+pub async fn show_website<S: Store>(
+    website_name: Path<String>,
+    caller: Option<ReqData<Caller>>,
+    store: Data<S>,
+) -> HomescreenResult<HttpResponse> {
+    info!("Showing hidden website");
+
+    let owner = owner(caller)?;
+    store
+        .set_website_hidden(&owner, website_name.as_str(), false)
+        .await
+        .inspect_err(|err| error!("Unable to show {} to {owner}, {err}", website_name.as_str()))?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Changes the order only the caller sees and returns their homescreen as it now looks
+pub async fn reorder_personal_websites<S: Store>(
+    Json(order): Json<WebsiteOrder>,
+    caller: Option<ReqData<Caller>>,
+    store: Data<S>,
+) -> HomescreenResult<HttpResponse> {
+    info!("Reordering personal websites");
+
+    let owner = owner(caller)?;
+    store
+        .reorder_personal_websites(&owner, order)
+        .await
+        .inspect_err(|err| error!("Unable to reorder personal websites of {owner}, {err}"))?;
+
+    let websites = store
+        .get_websites()
+        .await
+        .inspect_err(|err| error!("Unable to get websites, {err}"))?;
+    let overlay = store
+        .get_overlay(&owner)
+        .await
+        .inspect_err(|err| error!("Unable to get personal homescreen of {owner}, {err}"))?;
+
+    Ok(HttpResponse::Ok().json(overlay.apply(&websites)))
+}
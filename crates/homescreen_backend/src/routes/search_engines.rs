@@ -0,0 +1,80 @@
+use actix_web::{
+    web::{Data, Form, Path},
+    HttpResponse,
+};
+use homescreen_data::prelude::*;
+use homescreen_errors::prelude::*;
+use log::{error, info};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct SearchEngineForm {
+    name: String,
+    url_template: String,
+    prefix: String,
+    #[serde(default)]
+    is_default: bool,
+}
+impl TryFrom<SearchEngineForm> for SearchEngine {
+    type Error = HomescreenError;
+
+    fn try_from(
+        SearchEngineForm {
+            name,
+            url_template,
+            prefix,
+            is_default,
+        }: SearchEngineForm,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self::new(name, url_template, prefix)?.with_default(is_default))
+    }
+}
+
+pub async fn get_search_engines<S: Store>(store: Data<S>) -> HomescreenResult<HttpResponse> {
+    info!("Retrieving search engines");
+
+    let engines = store
+        .get_search_engines()
+        .await
+        .inspect_err(|err| error!("Unable to get search engines, {err}"))?;
+
+    Ok(HttpResponse::Ok().json(engines))
+}
+
+pub async fn create_or_update_search_engine<S: Store>(
+    Form(engine): Form<SearchEngineForm>,
+    store: Data<S>,
+) -> HomescreenResult<HttpResponse> {
+    info!("Inserting or updating search engine");
+
+    let engine: SearchEngine = engine
+        .try_into()
+        .inspect_err(|err| error!("Unable to parse search engine from form, {err}"))?;
+
+    store.create_or_update_search_engine(engine).await?;
+    let engines = store
+        .get_search_engines()
+        .await
+        .inspect_err(|err| error!("Unable to get search engines, {err}"))?;
+
+    Ok(HttpResponse::Ok().json(engines))
+}
+
+pub async fn delete_search_engine<S: Store>(
+    name: Path<String>,
+    store: Data<S>,
+) -> HomescreenResult<HttpResponse> {
+    info!("Deleting search engine");
+
+    store
+        .delete_search_engine(name.as_str())
+        .await
+        .inspect_err(|err| {
+            error!(
+                "Unable to delete search engine with name {}, {err}",
+                name.as_str()
+            )
+        })?;
+
+    Ok(HttpResponse::Ok().finish())
+}
@@ -0,0 +1,99 @@
+use actix_web::{
+    web::{Data, Form, Path},
+    HttpResponse,
+};
+use homescreen_data::prelude::*;
+use homescreen_errors::prelude::*;
+use log::{error, info};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct SectionForm {
+    display_name: String,
+    slug: String,
+    position: Option<i32>,
+}
+
+#[derive(Deserialize)]
+pub struct SectionPatchForm {
+    display_name: Option<String>,
+    slug: Option<String>,
+    position: Option<i32>,
+}
+
+pub async fn get_sections<S: Store>(store: Data<S>) -> HomescreenResult<HttpResponse> {
+    info!("Retrieving sections");
+
+    let sections = store
+        .get_sections()
+        .await
+        .inspect_err(|err| error!("Unable to get sections, {err}"))?;
+
+    Ok(HttpResponse::Ok().json(sections))
+}
+
+pub async fn get_section_websites<S: Store>(
+    slug: Path<String>,
+    store: Data<S>,
+) -> HomescreenResult<HttpResponse> {
+    info!("Retrieving {} websites", slug.as_str());
+
+    store.get_section(slug.as_str()).await?;
+    let websites = store
+        .get_section_websites(slug.as_str())
+        .await
+        .inspect_err(|err| error!("Unable to get {} websites, {err}", slug.as_str()))?;
+
+    Ok(HttpResponse::Ok().json(websites))
+}
+
+pub async fn create_section<S: Store>(
+    Form(SectionForm {
+        display_name,
+        slug,
+        position,
+    }): Form<SectionForm>,
+    store: Data<S>,
+) -> HomescreenResult<HttpResponse> {
+    info!("Inserting section");
+
+    let section = store
+        .create_section(display_name, slug, position)
+        .await
+        .inspect_err(|err| error!("Unable to create section, {err}"))?;
+
+    Ok(HttpResponse::Created().json(section))
+}
+
+pub async fn update_section<S: Store>(
+    slug: Path<String>,
+    Form(SectionPatchForm {
+        display_name,
+        slug: new_slug,
+        position,
+    }): Form<SectionPatchForm>,
+    store: Data<S>,
+) -> HomescreenResult<HttpResponse> {
+    info!("Updating section");
+
+    let section = store
+        .update_section(slug.as_str(), display_name, new_slug, position)
+        .await
+        .inspect_err(|err| error!("Unable to update section {}, {err}", slug.as_str()))?;
+
+    Ok(HttpResponse::Ok().json(section))
+}
+
+pub async fn delete_section<S: Store>(
+    slug: Path<String>,
+    store: Data<S>,
+) -> HomescreenResult<HttpResponse> {
+    info!("Deleting section");
+
+    store
+        .delete_section(slug.as_str())
+        .await
+        .inspect_err(|err| error!("Unable to delete section {}, {err}", slug.as_str()))?;
+
+    Ok(HttpResponse::Ok().finish())
+}
@@ -0,0 +1,84 @@
+use crate::auth::{SESSION_COOKIE, SESSION_LENGTH};
+use actix_web::{
+    cookie::{time::Duration, Cookie, SameSite},
+    web::{Data, Form, ReqData},
+    HttpRequest, HttpResponse,
+};
+use chrono::Utc;
+use homescreen_data::prelude::*;
+use homescreen_errors::prelude::*;
+use log::{error, info, warn};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct LoginForm {
+    name: String,
+    password: String,
+}
+
+/// The session cookie, only sent over https and kept away from scripts, other sites only get it
+/// sent along when they link here so identity providers can send people back logged in
+pub(crate) fn session_cookie(value: String) -> Cookie<'static> {
+    Cookie::build(SESSION_COOKIE, value)
+        .path("/")
+        .http_only(true)
+        .secure(true)
+        .same_site(SameSite::Lax)
+        .max_age(Duration::seconds(SESSION_LENGTH.num_seconds()))
+        .finish()
+}
+
+pub async fn log_in<S: Store>(
+    Form(LoginForm { name, password }): Form<LoginForm>,
+    store: Data<S>,
+) -> HomescreenResult<HttpResponse> {
+    info!("Logging in");
+
+    let user = match store.find_user(&name).await? {
+        Some((user, password_hash)) if User::verify_password(&password, &password_hash) => user,
+        _ => {
+            warn!("Rejected login for {name}");
+            return Err(ServerError::InvalidLogin.into());
+        }
+    };
+
+    let secret = User::new_session();
+    store
+        .create_session(
+            ApiToken::hash(&secret),
+            user.name(),
+            Utc::now() + SESSION_LENGTH,
+        )
+        .await
+        .inspect_err(|err| error!("Unable to create session for {name}, {err}"))?;
+
+    Ok(HttpResponse::Ok()
+        .cookie(session_cookie(secret))
+        .json(Caller::user(user.name().to_owned(), user.role().scope())))
+}
+
+pub async fn log_out<S: Store>(
+    request: HttpRequest,
+    store: Data<S>,
+) -> HomescreenResult<HttpResponse> {
+    info!("Logging out");
+
+    if let Some(session) = request.cookie(SESSION_COOKIE) {
+        store
+            .delete_session(&ApiToken::hash(session.value()))
+            .await
+            .inspect_err(|err| error!("Unable to delete session, {err}"))?;
+    }
+
+    let mut cookie = session_cookie(String::new());
+    cookie.make_removal();
+    Ok(HttpResponse::Ok().cookie(cookie).finish())
+}
+
+/// Who is making the request, so the frontend knows whether to show anything for editing
+pub async fn session(caller: Option<ReqData<Caller>>) -> HomescreenResult<HttpResponse> {
+    info!("Retrieving session");
+
+    let caller = caller.ok_or(ServerError::Unauthorized)?;
+    Ok(HttpResponse::Ok().json(caller.into_inner()))
+}
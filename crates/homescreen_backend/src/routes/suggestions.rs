@@ -0,0 +1,37 @@
+use actix_web::{
+    web::{Bytes, Data, Query},
+    HttpResponse,
+};
+use homescreen_data::prelude::*;
+use homescreen_errors::prelude::*;
+use log::{error, info};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct SuggestionQuery {
+    #[serde(default = "SuggestionQuery::default_limit")]
+    limit: usize,
+}
+impl SuggestionQuery {
+    fn default_limit() -> usize {
+        20
+    }
+}
+
+pub async fn suggest_websites<S: Store>(
+    Query(SuggestionQuery { limit }): Query<SuggestionQuery>,
+    history: Bytes,
+    store: Data<S>,
+) -> HomescreenResult<HttpResponse> {
+    info!("Suggesting websites from browser history");
+
+    let history = History::read(&history)
+        .await
+        .inspect_err(|err| error!("Unable to read browser history, {err}"))?;
+    let websites = store
+        .get_websites()
+        .await
+        .inspect_err(|err| error!("Unable to get websites, {err}"))?;
+
+    Ok(HttpResponse::Ok().json(history.suggest(&websites, limit)))
+}
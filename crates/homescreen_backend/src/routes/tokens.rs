@@ -0,0 +1,69 @@
+use crate::auth;
+use actix_web::{
+    web::{Data, Form, Path, ReqData},
+    HttpResponse,
+};
+use homescreen_data::prelude::*;
+use homescreen_errors::prelude::*;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize)]
+pub struct TokenForm {
+    name: String,
+    scope: Scope,
+}
+
+/// A token that was just created, the only response that ever includes the secret
+#[derive(Serialize)]
+pub struct CreatedToken {
+    #[serde(flatten)]
+    token: ApiToken,
+    secret: String,
+}
+
+pub async fn get_tokens<S: Store>(
+    caller: Option<ReqData<Caller>>,
+    store: Data<S>,
+) -> HomescreenResult<HttpResponse> {
+    info!("Retrieving api tokens");
+
+    auth::check_scope(caller.as_deref(), Scope::Admin, "GET /tokens")?;
+    let tokens = store
+        .get_tokens()
+        .await
+        .inspect_err(|err| error!("Unable to get api tokens, {err}"))?;
+
+    Ok(HttpResponse::Ok().json(tokens))
+}
+
+pub async fn create_token<S: Store>(
+    Form(TokenForm { name, scope }): Form<TokenForm>,
+    caller: Option<ReqData<Caller>>,
+    store: Data<S>,
+) -> HomescreenResult<HttpResponse> {
+    info!("Creating api token");
+
+    auth::check_scope(caller.as_deref(), Scope::Admin, "PUT /tokens")?;
+    let (token, secret) = auth::issue_token(store.get_ref(), name, scope)
+        .await
+        .inspect_err(|err| error!("Unable to create api token, {err}"))?;
+
+    Ok(HttpResponse::Created().json(CreatedToken { token, secret }))
+}
+
+pub async fn delete_token<S: Store>(
+    name: Path<String>,
+    caller: Option<ReqData<Caller>>,
+    store: Data<S>,
+) -> HomescreenResult<HttpResponse> {
+    info!("Deleting api token");
+
+    auth::check_scope(caller.as_deref(), Scope::Admin, "DELETE /tokens/{name}")?;
+    store
+        .delete_token(name.as_str())
+        .await
+        .inspect_err(|err| error!("Unable to delete api token {}, {err}", name.as_str()))?;
+
+    Ok(HttpResponse::Ok().finish())
+}
@@ -0,0 +1,70 @@
+use crate::auth;
+use actix_web::{
+    web::{Data, Form, Path, ReqData},
+    HttpResponse,
+};
+use homescreen_data::prelude::*;
+use homescreen_errors::prelude::*;
+use log::{error, info};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct UserForm {
+    name: String,
+    password: String,
+    role: Role,
+}
+
+pub async fn get_users<S: Store>(
+    caller: Option<ReqData<Caller>>,
+    store: Data<S>,
+) -> HomescreenResult<HttpResponse> {
+    info!("Retrieving users");
+
+    auth::check_scope(caller.as_deref(), Scope::Admin, "GET /users")?;
+    let users = store
+        .get_users()
+        .await
+        .inspect_err(|err| error!("Unable to get users, {err}"))?;
+
+    Ok(HttpResponse::Ok().json(users))
+}
+
+pub async fn create_or_update_user<S: Store>(
+    Form(UserForm {
+        name,
+        password,
+        role,
+    }): Form<UserForm>,
+    caller: Option<ReqData<Caller>>,
+    store: Data<S>,
+) -> HomescreenResult<HttpResponse> {
+    info!("Inserting or updating user");
+
+    auth::check_scope(caller.as_deref(), Scope::Admin, "PUT /users")?;
+    let created = auth::save_user(store.get_ref(), name, &password, role)
+        .await
+        .inspect_err(|err| error!("Unable to save user, {err}"))?;
+
+    Ok(if created {
+        HttpResponse::Created().finish()
+    } else {
+        HttpResponse::Ok().finish()
+    })
+}
+
+pub async fn delete_user<S: Store>(
+    name: Path<String>,
+    caller: Option<ReqData<Caller>>,
+    store: Data<S>,
+) -> HomescreenResult<HttpResponse> {
+    info!("Deleting user");
+
+    auth::check_scope(caller.as_deref(), Scope::Admin, "DELETE /users/{name}")?;
+    store
+        .delete_user(name.as_str())
+        .await
+        .inspect_err(|err| error!("Unable to delete user {}, {err}", name.as_str()))?;
+
+    Ok(HttpResponse::Ok().finish())
+}
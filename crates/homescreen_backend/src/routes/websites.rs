@@ -1,19 +1,39 @@
 use actix_web::{
-    delete, get, put,
-    web::{Data, Form, Path},
+    web::{Data, Form, Json, Path, Query, ReqData},
     HttpResponse,
 };
 use homescreen_data::prelude::*;
 use homescreen_errors::prelude::*;
 use log::{error, info};
 use serde::Deserialize;
-use sqlx::MySqlPool;
+
+/// Forms can't repeat a field so tags are sent as a single comma separated list
+fn split_tags(tags: &str) -> Vec<String> {
+    tags.split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+/// Forms can't leave out a value once the field is included so an empty alias or link template
+/// means there isn't one
+fn non_empty(value: String) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_owned())
+}
 
 #[derive(Deserialize)]
 pub struct WebsiteForm {
     website_name: String,
     website_link: String,
     section: String,
+    #[serde(default)]
+    tags: String,
+    #[serde(default)]
+    alias: String,
+    #[serde(default)]
+    link_template: String,
 }
 impl TryFrom<WebsiteForm> for Website {
     type Error = HomescreenError;
@@ -23,62 +43,145 @@ impl TryFrom<WebsiteForm> for Website {
             website_name,
             website_link,
             section,
+            tags,
+            alias,
+            link_template,
         }: WebsiteForm,
     ) -> Result<Self, Self::Error> {
-        Self::new(website_name, website_link, section)
+        Self::new(website_name, website_link, section)?
+            .with_tags(split_tags(&tags))?
+            .with_alias(non_empty(alias))?
+            .with_link_template(non_empty(link_template))
     }
 }
 
-#[get("/websites")]
-pub async fn get_websites(database: Data<Box<MySqlPool>>) -> HomescreenResult<HttpResponse> {
+/// Every field is optional so only the parts of the website that are included are changed
+#[derive(Deserialize)]
+pub struct WebsitePatchForm {
+    website_name: Option<String>,
+    website_link: Option<String>,
+    section: Option<String>,
+    tags: Option<String>,
+    alias: Option<String>,
+    link_template: Option<String>,
+}
+impl From<WebsitePatchForm> for WebsitePatch {
+    fn from(
+        WebsitePatchForm {
+            website_name,
+            website_link,
+            section,
+            tags,
+            alias,
+            link_template,
+        }: WebsitePatchForm,
+    ) -> Self {
+        let mut patch = Self::new();
+        if let Some(website_name) = website_name {
+            patch = patch.with_name(website_name);
+        }
+        if let Some(website_link) = website_link {
+            patch = patch.with_link(website_link);
+        }
+        if let Some(section) = section {
+            patch = patch.with_section(section);
+        }
+        if let Some(tags) = tags {
+            patch = patch.with_tags(split_tags(&tags));
+        }
+        if let Some(alias) = alias {
+            patch = patch.with_alias(non_empty(alias));
+        }
+        if let Some(link_template) = link_template {
+            patch = patch.with_link_template(non_empty(link_template));
+        }
+        patch
+    }
+}
+
+#[derive(Deserialize)]
+pub struct WebsiteQuery {
+    tag: Option<String>,
+    #[serde(default)]
+    order: WebsiteSort,
+}
+
+/// The shared homescreen, with the personal homescreen of whoever is logged in layered over it
+pub async fn get_websites<S: Store>(
+    Query(WebsiteQuery { tag, order }): Query<WebsiteQuery>,
+    caller: Option<ReqData<Caller>>,
+    store: Data<S>,
+) -> HomescreenResult<HttpResponse> {
     info!("Retrieving websites");
 
-    let websites = Website::get_websites(&database)
+    let mut websites = store
+        .get_websites()
         .await
         .inspect_err(|err| error!("Unable to get websites, {err}"))?;
 
-    Ok(HttpResponse::Ok().json(websites))
-}
+    if let Some(caller) = caller.filter(|caller| caller.is_user()) {
+        let overlay = store.get_overlay(caller.name()).await.inspect_err(|err| {
+            error!(
+                "Unable to get personal homescreen of {}, {err}",
+                caller.name()
+            )
+        })?;
+        websites = overlay.apply(&websites);
+    }
 
-#[get("/websites/coding")]
-pub async fn get_coding_websites(database: Data<Box<MySqlPool>>) -> HomescreenResult<HttpResponse> {
-    info!("Retrieving coding websites");
+    if let Some(tag) = tag {
+        websites
+            .iter_mut()
+            .for_each(|column| column.retain_tagged(&tag));
+    }
 
-    let websites = Website::get_coding_websites(&database)
-        .await
-        .inspect_err(|err| error!("Unable to get coding websites, {err}"))?;
+    if order == WebsiteSort::MostUsed {
+        let visits = store
+            .visit_counts()
+            .await
+            .inspect_err(|err| error!("Unable to get visit counts, {err}"))?;
+        websites
+            .iter_mut()
+            .for_each(|column| column.sort_by_visits(&visits));
+    }
 
     Ok(HttpResponse::Ok().json(websites))
 }
 
-#[get("/websites/fun")]
-pub async fn get_fun_website(database: Data<Box<MySqlPool>>) -> HomescreenResult<HttpResponse> {
-    info!("Retrieving fun websites");
+pub async fn website_stats<S: Store>(
+    website_name: Path<String>,
+    store: Data<S>,
+) -> HomescreenResult<HttpResponse> {
+    info!("Retrieving website stats");
 
-    let websites = Website::get_fun_websites(&database)
+    let stats = store
+        .website_stats(&website_name)
         .await
-        .inspect_err(|err| error!("Unable to get fun websites, {err}"))?;
+        .inspect_err(|err| error!("Unable to get stats for {website_name}, {err}"))?;
 
-    Ok(HttpResponse::Ok().json(websites))
+    Ok(HttpResponse::Ok().json(stats))
 }
 
-#[get("/websites/editing")]
-pub async fn get_editing_websites(
-    database: Data<Box<MySqlPool>>,
+pub async fn create_or_update_website<S: Store>(
+    Form(website): Form<WebsiteForm>,
+    store: Data<S>,
 ) -> HomescreenResult<HttpResponse> {
-    info!("Retrieving editing websites");
+    info!("Inserting or updating website");
 
-    let websites = Website::get_editing_websites(&database)
-        .await
-        .inspect_err(|err| error!("Unable to get editing websites, {err}"))?;
+    let website: Website = website
+        .try_into()
+        .inspect_err(|err| error!("Unable to parse website from form, {err}"))?;
 
-    Ok(HttpResponse::Ok().json(websites))
+    match store.create_or_update_website(website).await? {
+        WebsiteChange::Created => Ok(HttpResponse::Created().finish()),
+        WebsiteChange::Updated => Ok(HttpResponse::Ok().finish()),
+    }
 }
 
-#[put("/websites")]
-pub async fn create_or_update_website(
+/// Like [`create_or_update_website`] but refuses to touch a website that already has the name
+pub async fn create_website<S: Store>(
     Form(website): Form<WebsiteForm>,
-    database: Data<Box<MySqlPool>>,
+    store: Data<S>,
 ) -> HomescreenResult<HttpResponse> {
     info!("Inserting website");
 
@@ -86,18 +189,39 @@ pub async fn create_or_update_website(
         .try_into()
         .inspect_err(|err| error!("Unable to parse website from form, {err}"))?;
 
-    website.create_or_update_website(&database).await?;
+    store.create_website(website).await?;
+
     Ok(HttpResponse::Created().finish())
 }
 
-#[delete("/websites/{website_name}")]
-pub async fn delete_website(
+pub async fn update_website<S: Store>(
     website_name: Path<String>,
-    database: Data<Box<MySqlPool>>,
+    Form(patch): Form<WebsitePatchForm>,
+    store: Data<S>,
+) -> HomescreenResult<HttpResponse> {
+    info!("Updating website");
+
+    let website = store
+        .update_website(website_name.as_str(), patch.into())
+        .await
+        .inspect_err(|err| {
+            error!(
+                "Unable to update website with name {}, {err}",
+                website_name.as_str()
+            )
+        })?;
+
+    Ok(HttpResponse::Ok().json(website))
+}
+
+pub async fn delete_website<S: Store>(
+    website_name: Path<String>,
+    store: Data<S>,
 ) -> HomescreenResult<HttpResponse> {
     info!("Deleting websites");
 
-    Website::delete_websites(website_name.as_str(), &database)
+    store
+        .delete_website(website_name.as_str())
         .await
         .inspect_err(|err| {
             error!(
@@ -108,3 +232,22 @@ pub async fn delete_website(
 
     Ok(HttpResponse::Ok().finish())
 }
+
+pub async fn reorder_websites<S: Store>(
+    Json(order): Json<WebsiteOrder>,
+    store: Data<S>,
+) -> HomescreenResult<HttpResponse> {
+    info!("Reordering websites");
+
+    store
+        .reorder_websites(order)
+        .await
+        .inspect_err(|err| error!("Unable to reorder websites, {err}"))?;
+
+    let websites = store
+        .get_websites()
+        .await
+        .inspect_err(|err| error!("Unable to get websites, {err}"))?;
+
+    Ok(HttpResponse::Ok().json(websites))
+}
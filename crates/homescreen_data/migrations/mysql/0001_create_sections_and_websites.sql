@@ -0,0 +1,14 @@
+-- Databases that were set up by hand before migrations existed already have these tables
+CREATE TABLE IF NOT EXISTS sections(
+    section_id INT AUTO_INCREMENT PRIMARY KEY,
+    display_name VARCHAR(255) NOT NULL,
+    slug VARCHAR(255) UNIQUE NOT NULL,
+    position INT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS websites(
+    website_name VARCHAR(255) PRIMARY KEY,
+    website_link VARCHAR(255) UNIQUE NOT NULL,
+    section VARCHAR(255) NOT NULL,
+    FOREIGN KEY (section) REFERENCES sections(slug) ON UPDATE CASCADE
+);
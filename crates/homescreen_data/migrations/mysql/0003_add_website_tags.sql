@@ -0,0 +1,12 @@
+CREATE TABLE tags(
+    tag_id INT AUTO_INCREMENT PRIMARY KEY,
+    name VARCHAR(255) UNIQUE NOT NULL
+);
+
+CREATE TABLE website_tags(
+    website_name VARCHAR(255) NOT NULL,
+    tag_id INT NOT NULL,
+    PRIMARY KEY (website_name, tag_id),
+    FOREIGN KEY (website_name) REFERENCES websites(website_name) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (tag_id) REFERENCES tags(tag_id) ON DELETE CASCADE
+);
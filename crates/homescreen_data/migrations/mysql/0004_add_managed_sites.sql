@@ -0,0 +1,2 @@
+ALTER TABLE sections ADD COLUMN managed BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE websites ADD COLUMN managed BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,8 @@
+CREATE TABLE website_visits(
+    website_name VARCHAR(255) NOT NULL,
+    day VARCHAR(10) NOT NULL,
+    visits BIGINT NOT NULL,
+    last_visit BIGINT NOT NULL,
+    PRIMARY KEY (website_name, day),
+    FOREIGN KEY (website_name) REFERENCES websites(website_name) ON UPDATE CASCADE ON DELETE CASCADE
+);
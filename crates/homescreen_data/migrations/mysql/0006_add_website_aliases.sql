@@ -0,0 +1,3 @@
+ALTER TABLE websites ADD COLUMN alias VARCHAR(255) NULL;
+ALTER TABLE websites ADD COLUMN link_template VARCHAR(255) NULL;
+CREATE UNIQUE INDEX websites_alias ON websites(alias);
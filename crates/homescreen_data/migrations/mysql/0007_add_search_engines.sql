@@ -0,0 +1,7 @@
+CREATE TABLE search_engines(
+    search_engine_id INT AUTO_INCREMENT PRIMARY KEY,
+    name VARCHAR(255) UNIQUE NOT NULL,
+    url_template VARCHAR(2048) NOT NULL,
+    prefix VARCHAR(255) UNIQUE NOT NULL,
+    is_default BOOLEAN NOT NULL DEFAULT FALSE
+);
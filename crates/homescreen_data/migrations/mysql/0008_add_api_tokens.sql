@@ -0,0 +1,6 @@
+CREATE TABLE api_tokens(
+    name VARCHAR(255) PRIMARY KEY,
+    token_hash VARCHAR(64) UNIQUE NOT NULL,
+    scope VARCHAR(16) NOT NULL,
+    created_at BIGINT NOT NULL
+);
@@ -0,0 +1,13 @@
+CREATE TABLE users(
+    name VARCHAR(255) PRIMARY KEY,
+    password_hash VARCHAR(255) NOT NULL,
+    role VARCHAR(16) NOT NULL,
+    created_at BIGINT NOT NULL
+);
+
+CREATE TABLE sessions(
+    session_hash VARCHAR(64) PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    expires_at BIGINT NOT NULL,
+    FOREIGN KEY (name) REFERENCES users(name) ON DELETE CASCADE
+);
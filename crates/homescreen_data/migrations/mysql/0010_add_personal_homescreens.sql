@@ -0,0 +1,29 @@
+CREATE TABLE personal_websites(
+    owner VARCHAR(255) NOT NULL,
+    website_name VARCHAR(255) NOT NULL,
+    website_link VARCHAR(255) NOT NULL,
+    section VARCHAR(255) NOT NULL,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (owner, website_name),
+    FOREIGN KEY (owner) REFERENCES users(name) ON DELETE CASCADE,
+    FOREIGN KEY (section) REFERENCES sections(slug) ON UPDATE CASCADE ON DELETE RESTRICT
+);
+
+CREATE TABLE hidden_websites(
+    owner VARCHAR(255) NOT NULL,
+    website_name VARCHAR(255) NOT NULL,
+    PRIMARY KEY (owner, website_name),
+    FOREIGN KEY (owner) REFERENCES users(name) ON DELETE CASCADE,
+    FOREIGN KEY (website_name) REFERENCES websites(website_name) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+CREATE TABLE moved_websites(
+    owner VARCHAR(255) NOT NULL,
+    website_name VARCHAR(255) NOT NULL,
+    section VARCHAR(255) NOT NULL,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (owner, website_name),
+    FOREIGN KEY (owner) REFERENCES users(name) ON DELETE CASCADE,
+    FOREIGN KEY (website_name) REFERENCES websites(website_name) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (section) REFERENCES sections(slug) ON UPDATE CASCADE ON DELETE RESTRICT
+);
@@ -0,0 +1,30 @@
+-- Databases that were set up by hand before sections existed kept the section of each website as
+-- ENUM('code', 'fun', 'editing') through 0001, which left them with no sections for it to refer
+-- to. The hardcoded sections become rows and the column becomes a plain slug like everyone else's
+SET @hardcoded_sections = (
+    SELECT COUNT(*) FROM information_schema.COLUMNS
+    WHERE TABLE_SCHEMA = DATABASE()
+        AND TABLE_NAME = 'websites'
+        AND COLUMN_NAME = 'section'
+        AND DATA_TYPE = 'enum'
+);
+
+INSERT INTO sections(display_name, slug, position)
+SELECT hardcoded.display_name, hardcoded.slug, hardcoded.position
+FROM (
+    SELECT 'Code' AS display_name, 'code' AS slug, 0 AS position
+    UNION ALL SELECT 'Fun', 'fun', 1
+    UNION ALL SELECT 'Editing', 'editing', 2
+) AS hardcoded
+WHERE @hardcoded_sections > 0
+    AND hardcoded.slug NOT IN (SELECT slug FROM sections);
+
+-- MySql has no conditional DDL outside of stored procedures, so the statement is picked first
+SET @convert_sections = IF(
+    @hardcoded_sections > 0,
+    'ALTER TABLE websites MODIFY section VARCHAR(255) NOT NULL, ADD FOREIGN KEY (section) REFERENCES sections(slug) ON UPDATE CASCADE',
+    'DO 0'
+);
+PREPARE convert_sections FROM @convert_sections;
+EXECUTE convert_sections;
+DEALLOCATE PREPARE convert_sections;
@@ -0,0 +1,12 @@
+CREATE TABLE sections(
+    section_id SERIAL PRIMARY KEY,
+    display_name VARCHAR(255) NOT NULL,
+    slug VARCHAR(255) UNIQUE NOT NULL,
+    position INTEGER NOT NULL
+);
+
+CREATE TABLE websites(
+    website_name VARCHAR(255) PRIMARY KEY,
+    website_link VARCHAR(255) UNIQUE NOT NULL,
+    section VARCHAR(255) NOT NULL REFERENCES sections(slug) ON UPDATE CASCADE
+);
@@ -0,0 +1,10 @@
+CREATE TABLE tags(
+    tag_id SERIAL PRIMARY KEY,
+    name VARCHAR(255) UNIQUE NOT NULL
+);
+
+CREATE TABLE website_tags(
+    website_name VARCHAR(255) NOT NULL REFERENCES websites(website_name) ON UPDATE CASCADE ON DELETE CASCADE,
+    tag_id INTEGER NOT NULL REFERENCES tags(tag_id) ON DELETE CASCADE,
+    PRIMARY KEY (website_name, tag_id)
+);
@@ -0,0 +1,7 @@
+CREATE TABLE website_visits(
+    website_name VARCHAR(255) NOT NULL REFERENCES websites(website_name) ON UPDATE CASCADE ON DELETE CASCADE,
+    day VARCHAR(10) NOT NULL,
+    visits BIGINT NOT NULL,
+    last_visit BIGINT NOT NULL,
+    PRIMARY KEY (website_name, day)
+);
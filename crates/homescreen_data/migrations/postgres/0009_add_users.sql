@@ -0,0 +1,12 @@
+CREATE TABLE users(
+    name VARCHAR(255) PRIMARY KEY,
+    password_hash VARCHAR(255) NOT NULL,
+    role VARCHAR(16) NOT NULL,
+    created_at BIGINT NOT NULL
+);
+
+CREATE TABLE sessions(
+    session_hash VARCHAR(64) PRIMARY KEY,
+    name VARCHAR(255) NOT NULL REFERENCES users(name) ON DELETE CASCADE,
+    expires_at BIGINT NOT NULL
+);
@@ -0,0 +1,22 @@
+CREATE TABLE personal_websites(
+    owner VARCHAR(255) NOT NULL REFERENCES users(name) ON DELETE CASCADE,
+    website_name VARCHAR(255) NOT NULL,
+    website_link VARCHAR(255) NOT NULL,
+    section VARCHAR(255) NOT NULL REFERENCES sections(slug) ON UPDATE CASCADE ON DELETE RESTRICT,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (owner, website_name)
+);
+
+CREATE TABLE hidden_websites(
+    owner VARCHAR(255) NOT NULL REFERENCES users(name) ON DELETE CASCADE,
+    website_name VARCHAR(255) NOT NULL REFERENCES websites(website_name) ON UPDATE CASCADE ON DELETE CASCADE,
+    PRIMARY KEY (owner, website_name)
+);
+
+CREATE TABLE moved_websites(
+    owner VARCHAR(255) NOT NULL REFERENCES users(name) ON DELETE CASCADE,
+    website_name VARCHAR(255) NOT NULL REFERENCES websites(website_name) ON UPDATE CASCADE ON DELETE CASCADE,
+    section VARCHAR(255) NOT NULL REFERENCES sections(slug) ON UPDATE CASCADE ON DELETE RESTRICT,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (owner, website_name)
+);
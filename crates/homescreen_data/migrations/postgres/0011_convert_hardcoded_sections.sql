@@ -0,0 +1,2 @@
+-- Only MySql databases could have been set up by hand with hardcoded sections, this keeps the
+-- migrations numbered the same for every database
@@ -0,0 +1,12 @@
+CREATE TABLE sections(
+    section_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    display_name TEXT NOT NULL,
+    slug TEXT UNIQUE NOT NULL,
+    position INTEGER NOT NULL
+);
+
+CREATE TABLE websites(
+    website_name TEXT PRIMARY KEY,
+    website_link TEXT UNIQUE NOT NULL,
+    section TEXT NOT NULL REFERENCES sections(slug) ON UPDATE CASCADE
+);
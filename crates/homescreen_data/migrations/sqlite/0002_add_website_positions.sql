@@ -0,0 +1 @@
+ALTER TABLE websites ADD COLUMN position INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,10 @@
+CREATE TABLE tags(
+    tag_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT UNIQUE NOT NULL
+);
+
+CREATE TABLE website_tags(
+    website_name TEXT NOT NULL REFERENCES websites(website_name) ON UPDATE CASCADE ON DELETE CASCADE,
+    tag_id INTEGER NOT NULL REFERENCES tags(tag_id) ON DELETE CASCADE,
+    PRIMARY KEY (website_name, tag_id)
+);
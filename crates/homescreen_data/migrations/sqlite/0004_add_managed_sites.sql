@@ -0,0 +1,2 @@
+ALTER TABLE sections ADD COLUMN managed BOOLEAN NOT NULL DEFAULT 0;
+ALTER TABLE websites ADD COLUMN managed BOOLEAN NOT NULL DEFAULT 0;
@@ -0,0 +1,7 @@
+CREATE TABLE website_visits(
+    website_name TEXT NOT NULL REFERENCES websites(website_name) ON UPDATE CASCADE ON DELETE CASCADE,
+    day TEXT NOT NULL,
+    visits INTEGER NOT NULL,
+    last_visit INTEGER NOT NULL,
+    PRIMARY KEY (website_name, day)
+);
@@ -0,0 +1,3 @@
+ALTER TABLE websites ADD COLUMN alias TEXT NULL;
+ALTER TABLE websites ADD COLUMN link_template TEXT NULL;
+CREATE UNIQUE INDEX websites_alias ON websites(alias);
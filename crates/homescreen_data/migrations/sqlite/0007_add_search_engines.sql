@@ -0,0 +1,7 @@
+CREATE TABLE search_engines(
+    search_engine_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT UNIQUE NOT NULL,
+    url_template TEXT NOT NULL,
+    prefix TEXT UNIQUE NOT NULL,
+    is_default BOOLEAN NOT NULL DEFAULT 0
+);
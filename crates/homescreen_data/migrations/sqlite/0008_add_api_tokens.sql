@@ -0,0 +1,6 @@
+CREATE TABLE api_tokens(
+    name TEXT PRIMARY KEY,
+    token_hash TEXT UNIQUE NOT NULL,
+    scope TEXT NOT NULL,
+    created_at INTEGER NOT NULL
+);
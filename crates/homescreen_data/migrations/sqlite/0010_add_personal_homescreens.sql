@@ -0,0 +1,22 @@
+CREATE TABLE personal_websites(
+    owner TEXT NOT NULL REFERENCES users(name) ON DELETE CASCADE,
+    website_name TEXT NOT NULL,
+    website_link TEXT NOT NULL,
+    section TEXT NOT NULL REFERENCES sections(slug) ON UPDATE CASCADE ON DELETE RESTRICT,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (owner, website_name)
+);
+
+CREATE TABLE hidden_websites(
+    owner TEXT NOT NULL REFERENCES users(name) ON DELETE CASCADE,
+    website_name TEXT NOT NULL REFERENCES websites(website_name) ON UPDATE CASCADE ON DELETE CASCADE,
+    PRIMARY KEY (owner, website_name)
+);
+
+CREATE TABLE moved_websites(
+    owner TEXT NOT NULL REFERENCES users(name) ON DELETE CASCADE,
+    website_name TEXT NOT NULL REFERENCES websites(website_name) ON UPDATE CASCADE ON DELETE CASCADE,
+    section TEXT NOT NULL REFERENCES sections(slug) ON UPDATE CASCADE ON DELETE RESTRICT,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (owner, website_name)
+);
@@ -0,0 +1,3 @@
+ALTER TABLE users ADD COLUMN oidc_issuer TEXT NULL;
+ALTER TABLE users ADD COLUMN oidc_subject TEXT NULL;
+CREATE UNIQUE INDEX users_oidc_account ON users(oidc_issuer, oidc_subject);
@@ -0,0 +1,130 @@
+use crate::{
+    search_engines::SearchEngine,
+    sections::{SectionWebsites, WebsiteSection},
+    websites::Website,
+};
+use homescreen_errors::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Bumped whenever the layout of [`Backup`] changes so older backups can still be read
+pub const BACKUP_VERSION: u32 = 2;
+
+/// The first version that includes the search engines, older backups leave them untouched
+const SEARCH_ENGINES_VERSION: u32 = 2;
+
+/// Everything on the homescreen, the websites are listed in the order they are shown. Visit
+/// counts and personal homescreens are not part of it
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct Backup {
+    version: u32,
+    #[serde(default)]
+    sections: Vec<WebsiteSection>,
+    #[serde(default)]
+    websites: Vec<Website>,
+    #[serde(default)]
+    search_engines: Vec<SearchEngine>,
+}
+impl Backup {
+    pub fn new(columns: &[SectionWebsites], search_engines: &[SearchEngine]) -> Self {
+        Self {
+            version: BACKUP_VERSION,
+            sections: columns
+                .iter()
+                .map(|column| column.section().clone())
+                .collect(),
+            websites: columns
+                .iter()
+                .flat_map(|column| column.websites())
+                .cloned()
+                .collect(),
+            search_engines: search_engines.to_vec(),
+        }
+    }
+    /// Backups can be written by hand so everything in them is checked the same way the
+    /// forms are
+    pub fn validate(self) -> HomescreenResult<Self> {
+        if self.version > BACKUP_VERSION {
+            return Err(ServerError::UnsupportedBackupVersion(self.version).into());
+        }
+
+        let sections = self
+            .sections
+            .into_iter()
+            .map(|section| {
+                Ok(WebsiteSection {
+                    slug: WebsiteSection::validate_slug(section.slug)?,
+                    ..section
+                })
+            })
+            .collect::<HomescreenResult<_>>()?;
+        let websites = self
+            .websites
+            .into_iter()
+            .map(|website| {
+                Website::new(website.website_name, website.website_link, website.section)?
+                    .with_tags(website.tags)?
+                    .with_alias(website.alias)?
+                    .with_link_template(website.link_template)
+            })
+            .collect::<HomescreenResult<_>>()?;
+        let search_engines: Vec<SearchEngine> = self
+            .search_engines
+            .into_iter()
+            .map(|engine| {
+                Ok(
+                    SearchEngine::new(engine.name, engine.url_template, engine.prefix)?
+                        .with_default(engine.is_default),
+                )
+            })
+            .collect::<HomescreenResult<_>>()?;
+        if search_engines
+            .iter()
+            .filter(|engine| engine.is_default)
+            .count()
+            > 1
+        {
+            return Err(ServerError::CannotParseBackup(
+                "more than one search engine is the default".into(),
+            )
+            .into());
+        }
+
+        Ok(Self {
+            version: self.version,
+            sections,
+            websites,
+            search_engines,
+        })
+    }
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+    pub fn sections(&self) -> &[WebsiteSection] {
+        &self.sections
+    }
+    pub fn websites(&self) -> &[Website] {
+        &self.websites
+    }
+    pub fn search_engines(&self) -> &[SearchEngine] {
+        &self.search_engines
+    }
+    /// Backups from before search engines were included don't replace them
+    pub fn has_search_engines(&self) -> bool {
+        self.version >= SEARCH_ENGINES_VERSION
+    }
+}
+
+/// How a backup is combined with what is already on the homescreen
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum ImportStrategy {
+    /// Only the sections, websites and search engines in the backup are left. Visit counts of
+    /// websites that are still there are kept
+    Replace,
+    /// Sections and websites that already exist are left alone, as are websites whose link is
+    /// already used by another website
+    #[default]
+    MergeKeepExisting,
+    /// Sections and websites that already exist are changed to match the backup
+    MergeOverwrite,
+}
@@ -0,0 +1,474 @@
+use crate::{
+    sections::{SectionWebsites, WebsiteSection},
+    websites::Website,
+};
+use homescreen_errors::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+
+/// The folder bookmarks end up in when they are not inside any folder
+const DEFAULT_FOLDER: &str = "Bookmarks";
+
+/// The longest name or link the database columns can hold
+const MAX_LENGTH: usize = 255;
+
+/// A bookmark read from a browser, before it has been turned into a [`Website`]
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct Bookmark {
+    folder: String,
+    title: String,
+    url: String,
+}
+impl Bookmark {
+    pub fn new(folder: String, title: String, url: String) -> Self {
+        Self { folder, title, url }
+    }
+    /// Reads every bookmark out of a Netscape bookmark file, the `bookmarks.html` that browsers
+    /// export. Each bookmark belongs to the folder it is directly inside of
+    ///
+    /// ```rust
+    /// # use homescreen_data::prelude::*;
+    /// let html = r#"
+    ///     <H1>Bookmarks</H1>
+    ///     <DL><p>
+    ///         <DT><H3>Rust &amp; Cargo</H3>
+    ///         <DL><p>
+    ///             <DT><A HREF="https://docs.rs/" ADD_DATE="0">Docs</A>
+    ///         </DL><p>
+    ///         <DT><A HREF="https://github.com">GitHub</A>
+    ///     </DL><p>
+    /// "#;
+    /// let bookmarks = Bookmark::parse_netscape(html);
+    /// assert_eq!(bookmarks[0].folder(), "Rust & Cargo");
+    /// assert_eq!(bookmarks[0].url(), "https://docs.rs/");
+    /// assert_eq!(bookmarks[1].folder(), "Bookmarks");
+    /// assert_eq!(bookmarks[1].title(), "GitHub");
+    /// ```
+    pub fn parse_netscape(html: &str) -> Vec<Self> {
+        // Lowercasing ascii keeps every byte where it was, so positions found in one can be used
+        // in the other
+        let lower = html.to_ascii_lowercase();
+        let mut bookmarks = Vec::new();
+        let mut folders: Vec<String> = Vec::new();
+        let mut heading: Option<String> = None;
+        let mut position = 0;
+
+        while let Some(start) = lower[position..].find('<').map(|start| position + start) {
+            let Some(end) = lower[start..].find('>').map(|end| start + end) else {
+                break;
+            };
+            position = end + 1;
+
+            let tag = &html[start + 1..end];
+            let name = lower[start + 1..end]
+                .split(|char: char| char.is_whitespace())
+                .next()
+                .unwrap_or_default();
+
+            match name {
+                "h1" | "h3" => {
+                    let (text, after) = element_text(html, &lower, position, name);
+                    heading = Some(decode_entities(text.trim()));
+                    position = after;
+                }
+                "dl" => {
+                    let folder = heading
+                        .take()
+                        .or_else(|| folders.last().cloned())
+                        .unwrap_or_else(|| DEFAULT_FOLDER.to_owned());
+                    folders.push(folder);
+                }
+                "/dl" => {
+                    folders.pop();
+                }
+                "a" => {
+                    let (text, after) = element_text(html, &lower, position, name);
+                    position = after;
+
+                    let Some(url) = attribute(tag, "href") else {
+                        continue;
+                    };
+                    bookmarks.push(Self {
+                        folder: folders
+                            .last()
+                            .cloned()
+                            .unwrap_or_else(|| DEFAULT_FOLDER.to_owned()),
+                        title: decode_entities(text.trim()),
+                        url,
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        bookmarks
+    }
+    /// Reads every bookmark out of the `Bookmarks` file that Chromium based browsers keep in their
+    /// profile directory, each bookmark belongs to the folder it is directly inside of
+    ///
+    /// ```rust
+    /// # use homescreen_data::prelude::*;
+    /// let json = r#"{
+    ///     "roots": {
+    ///         "bookmark_bar": {
+    ///             "type": "folder",
+    ///             "name": "Bookmarks bar",
+    ///             "children": [
+    ///                 { "type": "url", "name": "GitHub", "url": "https://github.com/" },
+    ///                 {
+    ///                     "type": "folder",
+    ///                     "name": "Rust",
+    ///                     "children": [{ "type": "url", "name": "Docs", "url": "https://docs.rs/" }]
+    ///                 }
+    ///             ]
+    ///         }
+    ///     }
+    /// }"#;
+    /// let bookmarks = Bookmark::parse_chromium(json).unwrap();
+    /// assert_eq!(bookmarks[0].folder(), "Bookmarks bar");
+    /// assert_eq!(bookmarks[1].folder(), "Rust");
+    /// assert_eq!(bookmarks[1].url(), "https://docs.rs/");
+    /// ```
+    pub fn parse_chromium(json: &str) -> HomescreenResult<Vec<Self>> {
+        #[derive(Deserialize)]
+        struct BookmarksFile {
+            roots: Map<String, Value>,
+        }
+
+        let file: BookmarksFile = serde_json::from_str(json)
+            .map_err(|err| ServerError::CannotParseBookmarks(err.to_string()))?;
+
+        let mut bookmarks = Vec::new();
+        // The roots are listed in the order the browser shows them, anything else kept alongside
+        // them isn't a folder
+        for root in ["bookmark_bar", "other", "synced"]
+            .into_iter()
+            .filter_map(|root| file.roots.get(root))
+        {
+            if let Ok(root) = ChromiumNode::deserialize(root) {
+                root.collect(DEFAULT_FOLDER, &mut bookmarks);
+            }
+        }
+
+        Ok(bookmarks)
+    }
+    /// Writes every website into a Netscape bookmark file that browsers can import, each section
+    /// becomes a folder
+    ///
+    /// ```rust
+    /// # use homescreen_data::prelude::*;
+    /// let html = Bookmark::write_netscape(&[]);
+    /// assert!(html.starts_with("<!DOCTYPE NETSCAPE-Bookmark-file-1>"));
+    /// assert!(Bookmark::parse_netscape(&html).is_empty());
+    /// ```
+    pub fn write_netscape(columns: &[SectionWebsites]) -> String {
+        let mut html = String::from(concat!(
+            "<!DOCTYPE NETSCAPE-Bookmark-file-1>\n",
+            "<META HTTP-EQUIV=\"Content-Type\" CONTENT=\"text/html; charset=UTF-8\">\n",
+            "<TITLE>Bookmarks</TITLE>\n",
+            "<H1>Bookmarks</H1>\n",
+            "<DL><p>\n",
+        ));
+
+        for column in columns {
+            html.push_str(&format!(
+                "    <DT><H3>{}</H3>\n    <DL><p>\n",
+                encode_entities(column.section().display_name())
+            ));
+            for website in column.websites() {
+                let tags = website.tags().iter().cloned().collect::<Vec<_>>().join(",");
+                let tags = if tags.is_empty() {
+                    String::new()
+                } else {
+                    format!(" TAGS=\"{}\"", encode_entities(&tags))
+                };
+
+                html.push_str(&format!(
+                    "        <DT><A HREF=\"{}\"{tags}>{}</A>\n",
+                    encode_entities(&website.url()),
+                    encode_entities(website.name())
+                ));
+            }
+            html.push_str("    </DL><p>\n");
+        }
+
+        html.push_str("</DL><p>\n");
+        html
+    }
+    pub fn folder(&self) -> &str {
+        &self.folder
+    }
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+    /// The url without its scheme, the way [`Website`] links are stored. Only http and https
+    /// bookmarks can be websites so anything else is `None`
+    ///
+    /// ```rust
+    /// # use homescreen_data::prelude::*;
+    /// let bookmark = |url: &str| Bookmark::new("Code".into(), "Code".into(), url.into());
+    /// assert_eq!(bookmark("https://github.com/").website_link().unwrap(), "github.com");
+    /// assert_eq!(bookmark("HTTP://docs.rs/std").website_link().unwrap(), "docs.rs/std");
+    /// assert!(bookmark("javascript:alert(1)").website_link().is_none());
+    /// ```
+    pub fn website_link(&self) -> Option<String> {
+        let (scheme, link) = self.url.split_once("://")?;
+        let link = link.trim_end_matches('/');
+
+        (["http", "https"].contains(&scheme.to_ascii_lowercase().as_str()) && !link.is_empty())
+            .then(|| link.to_owned())
+    }
+    /// The name the website gets, bookmarks without a title are named after their link
+    pub fn website_name(&self) -> String {
+        if self.title.is_empty() {
+            self.website_link().unwrap_or_else(|| self.url.clone())
+        } else {
+            self.title.clone()
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum ChromiumNode {
+    Folder {
+        name: String,
+        #[serde(default)]
+        children: Vec<ChromiumNode>,
+    },
+    Url {
+        name: String,
+        url: String,
+    },
+}
+impl ChromiumNode {
+    fn collect(self, folder: &str, bookmarks: &mut Vec<Bookmark>) {
+        match self {
+            Self::Folder { name, children } => children
+                .into_iter()
+                .for_each(|child| child.collect(&name, bookmarks)),
+            Self::Url { name, url } => bookmarks.push(Bookmark::new(folder.to_owned(), name, url)),
+        }
+    }
+}
+
+/// The text from `position` up to the closing tag of `name`, alongside the position just after
+/// that closing tag
+fn element_text<'a>(html: &'a str, lower: &str, position: usize, name: &str) -> (&'a str, usize) {
+    let closing = format!("</{name}");
+
+    match lower[position..].find(&closing) {
+        Some(length) => {
+            let end = position + length;
+            let after = lower[end..]
+                .find('>')
+                .map_or(lower.len(), |close| end + close + 1);
+            (&html[position..end], after)
+        }
+        None => (&html[position..], html.len()),
+    }
+}
+
+/// The decoded value of the attribute `name` in the inside of a tag
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let mut rest = tag.split_once(char::is_whitespace)?.1;
+
+    loop {
+        rest = rest.trim_start();
+        let key_end = rest.find(|char: char| char == '=' || char.is_whitespace())?;
+        let key = &rest[..key_end];
+        rest = rest[key_end..].trim_start();
+
+        let Some(value) = rest.strip_prefix('=') else {
+            continue;
+        };
+        let value = value.trim_start();
+        let (value, after) = match value.chars().next()? {
+            quote @ ('"' | '\'') => {
+                let value = &value[1..];
+                let end = value.find(quote).unwrap_or(value.len());
+                (&value[..end], &value[(end + 1).min(value.len())..])
+            }
+            _ => {
+                let end = value.find(char::is_whitespace).unwrap_or(value.len());
+                (&value[..end], &value[end..])
+            }
+        };
+
+        if key.eq_ignore_ascii_case(name) {
+            return Some(decode_entities(value));
+        }
+        rest = after;
+    }
+}
+
+fn encode_entities(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// A bookmark that wasn't imported and why
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct ImportIssue {
+    bookmark: Bookmark,
+    reason: String,
+}
+impl ImportIssue {
+    pub fn bookmark(&self) -> &Bookmark {
+        &self.bookmark
+    }
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
+
+/// What happened to every bookmark in an import, when it is a dry run nothing was written but the
+/// report is the same as it would have been
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug, Default)]
+pub struct ImportReport {
+    dry_run: bool,
+    created_sections: Vec<String>,
+    imported: Vec<Website>,
+    skipped: Vec<ImportIssue>,
+    conflicting: Vec<ImportIssue>,
+}
+impl ImportReport {
+    pub fn new(dry_run: bool) -> Self {
+        Self {
+            dry_run,
+            ..Self::default()
+        }
+    }
+    pub fn created_section(&mut self, slug: String) {
+        self.created_sections.push(slug);
+    }
+    pub fn imported(&mut self, website: Website) {
+        self.imported.push(website);
+    }
+    /// The bookmark can't be a website or is already on the homescreen
+    pub fn skipped(&mut self, bookmark: Bookmark, reason: impl ToString) {
+        self.skipped.push(ImportIssue {
+            bookmark,
+            reason: reason.to_string(),
+        });
+    }
+    /// The bookmark clashes with a website that is already on the homescreen
+    pub fn conflicting(&mut self, bookmark: Bookmark, reason: impl ToString) {
+        self.conflicting.push(ImportIssue {
+            bookmark,
+            reason: reason.to_string(),
+        });
+    }
+    pub fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+    /// The slugs of the sections that were made for folders that had no section yet
+    pub fn created_sections(&self) -> &[String] {
+        &self.created_sections
+    }
+    pub fn imported_websites(&self) -> &[Website] {
+        &self.imported
+    }
+    pub fn skipped_bookmarks(&self) -> &[ImportIssue] {
+        &self.skipped
+    }
+    pub fn conflicting_bookmarks(&self) -> &[ImportIssue] {
+        &self.conflicting
+    }
+    /// Works out what importing `bookmarks` does without writing anything, each folder becomes a
+    /// section and bookmarks that clash with a website already on the homescreen are left alone.
+    /// `websites` are the names and links of the shared websites, `personal` the names of
+    /// everyone's personal websites and `sections` the slugs of every section. Returns the report
+    /// alongside the display name and slug of each section that has to be created for it
+    pub(crate) fn plan(
+        bookmarks: Vec<Bookmark>,
+        websites: impl IntoIterator<Item = (String, String)>,
+        personal: HashSet<String>,
+        mut sections: HashSet<String>,
+        dry_run: bool,
+    ) -> (Self, Vec<(String, String)>) {
+        let mut links: HashMap<String, String> = HashMap::new();
+        let mut owners: HashMap<String, String> = HashMap::new();
+        for (name, link) in websites {
+            owners.insert(link.clone(), name.clone());
+            links.insert(name, link);
+        }
+
+        let mut report = Self::new(dry_run);
+        let mut new_sections = Vec::new();
+        for bookmark in bookmarks {
+            let Some(link) = bookmark.website_link() else {
+                report.skipped(bookmark, "only http and https links can be imported");
+                continue;
+            };
+            let name = bookmark.website_name();
+            if name.chars().count() > MAX_LENGTH || link.chars().count() > MAX_LENGTH {
+                report.skipped(bookmark, "the name or link is too long");
+                continue;
+            }
+
+            match links.get(&name) {
+                Some(existing) if *existing == link => {
+                    report.skipped(
+                        bookmark,
+                        "a website with the same name and link already exists",
+                    );
+                    continue;
+                }
+                Some(existing) => {
+                    report.conflicting(
+                        bookmark,
+                        format!("a website with the same name already links to {existing}"),
+                    );
+                    continue;
+                }
+                None => {}
+            }
+            if personal.contains(&name) {
+                report.conflicting(
+                    bookmark,
+                    "someone already has a personal website with the same name",
+                );
+                continue;
+            }
+            if let Some(owner) = owners.get(&link) {
+                report.conflicting(bookmark, format!("{owner} already uses the same link"));
+                continue;
+            }
+
+            let slug = WebsiteSection::slug_from(bookmark.folder());
+            let website = match Website::new(name.clone(), link.clone(), slug.clone()) {
+                Ok(website) => website,
+                Err(err) => {
+                    report.skipped(bookmark, err);
+                    continue;
+                }
+            };
+
+            if sections.insert(slug.clone()) {
+                new_sections.push((bookmark.folder().to_owned(), slug.clone()));
+                report.created_section(slug);
+            }
+
+            links.insert(name.clone(), link.clone());
+            owners.insert(link, name);
+            report.imported(website);
+        }
+
+        (report, new_sections)
+    }
+}
@@ -0,0 +1,144 @@
+use crate::{bookmarks::Bookmark, suggestions::History};
+use homescreen_errors::prelude::*;
+use log::error;
+use sqlx::{
+    sqlite::{SqliteConnectOptions, SqliteConnection},
+    ConnectOptions,
+};
+use std::{
+    env,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+};
+
+/// A browser database that has been uploaded, SQLite can only open files so it is written to a
+/// temporary file that is removed again once this is dropped
+pub(crate) struct UploadedDatabase {
+    path: PathBuf,
+}
+impl UploadedDatabase {
+    pub(crate) fn new(contents: &[u8]) -> HomescreenResult<Self> {
+        // The name can't be guessed and the file has to be new, so nobody else can have put a
+        // link to somewhere else there first
+        let path = env::temp_dir().join(format!(
+            "{}.sqlite",
+            crate::tokens::random_secret("homescreen_upload_")
+        ));
+        let store_error = |err| {
+            let err = HomescreenError::from(ServerError::CannotStoreUpload(err));
+            error!("Cannot store uploaded database, {err}");
+            err
+        };
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .map_err(store_error)?;
+        // Removes the file again if writing to it fails
+        let upload = Self { path };
+        file.write_all(contents).map_err(store_error)?;
+
+        Ok(upload)
+    }
+    /// Opens the database without changing it, browsers keep their databases in WAL mode which
+    /// would otherwise need files that weren't uploaded
+    pub(crate) async fn connect(&self) -> HomescreenResult<SqliteConnection> {
+        SqliteConnectOptions::new()
+            .filename(&self.path)
+            .read_only(true)
+            .immutable(true)
+            .connect()
+            .await
+            .map_err(ServerError::CannotReadBrowserDatabase)
+            .map_err(HomescreenError::from)
+    }
+}
+impl Drop for UploadedDatabase {
+    fn drop(&mut self) {
+        if let Err(err) = fs::remove_file(&self.path) {
+            error!("Cannot remove {}, {err}", self.path.display());
+        }
+    }
+}
+
+/// The folders Firefox puts every other folder in, they are stored with short names that the
+/// browser translates when it shows them
+const FIREFOX_ROOTS: [(&str, &str); 4] = [
+    ("menu________", "Bookmarks Menu"),
+    ("toolbar_____", "Bookmarks Toolbar"),
+    ("unfiled_____", "Other Bookmarks"),
+    ("mobile______", "Mobile Bookmarks"),
+];
+
+impl Bookmark {
+    /// Reads every bookmark out of a Firefox `places.sqlite` file, each bookmark belongs to the
+    /// folder it is directly inside of. Tags are kept as folders in the same table so those are
+    /// left out
+    pub async fn read_firefox(places: &[u8]) -> HomescreenResult<Vec<Self>> {
+        let database = UploadedDatabase::new(places)?;
+        let mut connection = database.connect().await?;
+
+        let bookmarks: Vec<(String, Option<String>, Option<String>, String)> = sqlx::query_as(
+            "SELECT parent.guid, parent.title, bookmark.title, place.url \
+             FROM moz_bookmarks AS bookmark \
+             JOIN moz_places AS place ON place.id = bookmark.fk \
+             JOIN moz_bookmarks AS parent ON parent.id = bookmark.parent \
+             WHERE bookmark.type = 1 \
+             AND parent.parent <> COALESCE((SELECT id FROM moz_bookmarks WHERE guid = 'tags________'), -1) \
+             ORDER BY bookmark.parent, bookmark.position",
+        )
+        .fetch_all(&mut connection)
+        .await
+        .map_err(ServerError::CannotReadBrowserDatabase)
+        .map_err(HomescreenError::from)
+        .inspect_err(|err| error!("Cannot read firefox bookmarks, {err}"))?;
+
+        Ok(bookmarks
+            .into_iter()
+            .map(|(guid, folder, title, url)| {
+                let folder = FIREFOX_ROOTS
+                    .iter()
+                    .find(|(root, _)| *root == guid)
+                    .map(|(_, name)| name.to_string())
+                    .or(folder)
+                    .unwrap_or_default();
+                Self::new(folder, title.unwrap_or_default(), url)
+            })
+            .collect())
+    }
+}
+
+impl History {
+    /// Reads how often every page was visited out of a Firefox `places.sqlite` or a Chromium
+    /// `History` file, which one it is gets worked out from the tables inside it
+    pub async fn read(database: &[u8]) -> HomescreenResult<Self> {
+        let database = UploadedDatabase::new(database)?;
+        let mut connection = database.connect().await?;
+
+        let firefox: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'moz_places')",
+        )
+        .fetch_one(&mut connection)
+        .await
+        .map_err(ServerError::CannotReadBrowserDatabase)?;
+        let query = if firefox {
+            "SELECT url, visit_count FROM moz_places WHERE visit_count > 0"
+        } else {
+            "SELECT url, visit_count FROM urls WHERE visit_count > 0"
+        };
+
+        let pages: Vec<(String, i64)> = sqlx::query_as(query)
+            .fetch_all(&mut connection)
+            .await
+            .map_err(ServerError::CannotReadBrowserDatabase)
+            .map_err(HomescreenError::from)
+            .inspect_err(|err| error!("Cannot read browser history, {err}"))?;
+
+        let mut history = Self::default();
+        for (url, visits) in pages {
+            history.visit(&url, visits.try_into().unwrap_or_default());
+        }
+        Ok(history)
+    }
+}
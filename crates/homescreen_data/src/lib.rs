@@ -1,3 +1,18 @@
+pub(crate) mod backup;
+pub(crate) mod bookmarks;
+#[cfg(feature = "poison_wasm")]
+pub(crate) mod browsers;
+pub(crate) mod personal;
 pub mod prelude;
+pub(crate) mod proxies;
+pub(crate) mod search_engines;
+pub(crate) mod sections;
+pub(crate) mod sites;
+#[cfg(feature = "poison_wasm")]
+pub(crate) mod store;
+pub(crate) mod suggestions;
+pub(crate) mod tokens;
+pub(crate) mod users;
+pub(crate) mod visits;
 pub(crate) mod websites;
 
@@ -0,0 +1,185 @@
+use crate::{
+    sections::{SectionWebsites, WebsiteSection},
+    websites::{Website, WebsiteOrder},
+};
+use homescreen_errors::prelude::*;
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// Someone's own changes to the shared homescreen, the websites only they see, the shared websites
+/// they have hidden and where they have moved shared websites to
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Overlay {
+    /// Alongside their position among the moved websites of their section
+    pub(crate) websites: Vec<(Website, i32)>,
+    /// Names of the shared websites that aren't shown
+    pub(crate) hidden: BTreeSet<String>,
+    /// The section and position of every shared website that has been moved, keyed by its name
+    pub(crate) moved: HashMap<String, (String, i32)>,
+}
+impl Overlay {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Adds a personal website after the others in its section, personal websites only have a
+    /// name, link and section
+    pub fn with_website(mut self, website: Website) -> Self {
+        let position = self.next_position(website.section());
+        self.websites.push((
+            Website {
+                tags: BTreeSet::new(),
+                alias: None,
+                link_template: None,
+                personal: true,
+                ..website
+            },
+            position,
+        ));
+        self
+    }
+    /// Hides the shared website called `website_name`
+    pub fn with_hidden(mut self, website_name: String) -> Self {
+        self.hidden.insert(website_name);
+        self
+    }
+    pub fn websites(&self) -> impl Iterator<Item = &Website> {
+        self.websites.iter().map(|(website, _)| website)
+    }
+    pub fn website(&self, website_name: &str) -> Option<&Website> {
+        self.websites()
+            .find(|website| website.website_name == website_name)
+    }
+    pub fn hidden(&self) -> &BTreeSet<String> {
+        &self.hidden
+    }
+    /// The position that puts a website after every personal or moved website in `section`
+    pub(crate) fn next_position(&self, section: &str) -> i32 {
+        let personal = self
+            .websites
+            .iter()
+            .filter(|(website, _)| website.section == section)
+            .map(|(_, position)| *position);
+        let moved = self
+            .moved
+            .values()
+            .filter(|(moved_to, _)| moved_to == section)
+            .map(|(_, position)| *position);
+
+        personal.chain(moved).max().map_or(0, |last| last + 1)
+    }
+    /// Layers the overlay over the shared homescreen. Personal websites and the shared websites
+    /// that have been moved come first in each section, followed by the rest of the shared
+    /// websites in their usual order
+    pub fn apply(&self, shared: &[SectionWebsites]) -> Box<[SectionWebsites]> {
+        // Moved and personal websites sort before the rest by their personal position, the rest
+        // keep their shared order
+        type Placed = ((bool, i32), Website);
+        let mut columns: Vec<(WebsiteSection, Vec<Placed>)> = shared
+            .iter()
+            .map(|column| (column.section().clone(), Vec::new()))
+            .collect();
+        let mut place = |key: (bool, i32), website: Website| {
+            if let Some((_, websites)) = columns
+                .iter_mut()
+                .find(|(section, _)| section.slug() == website.section)
+            {
+                websites.push((key, website));
+            }
+        };
+
+        for column in shared {
+            for (index, website) in column.websites().iter().enumerate() {
+                // A personal website with the same name wins over the shared one
+                if self.hidden.contains(&website.website_name)
+                    || self.website(&website.website_name).is_some()
+                {
+                    continue;
+                }
+                match self.moved.get(&website.website_name) {
+                    Some((section, position)) => place(
+                        (false, *position),
+                        Website {
+                            section: section.clone(),
+                            ..website.clone()
+                        },
+                    ),
+                    None => place((true, index as i32), website.clone()),
+                }
+            }
+        }
+        for (website, position) in &self.websites {
+            place((false, *position), website.clone());
+        }
+
+        columns
+            .into_iter()
+            .map(|(section, mut websites)| {
+                websites.sort_by_key(|(key, _)| *key);
+                let websites = websites.into_iter().map(|(_, website)| website).collect();
+                SectionWebsites::new(section, websites)
+            })
+            .collect()
+    }
+    /// Puts the websites in `order` first in each listed section in the given order, `shared` is
+    /// the name of every shared website. Shared websites that had been moved into a listed
+    /// section but aren't listed go back to where the shared homescreen has them, personal
+    /// websites that aren't listed stay after the listed ones
+    ///
+    /// ```rust
+    /// # use homescreen_data::prelude::*;
+    /// # use std::collections::HashSet;
+    /// let mut overlay = Overlay::new()
+    ///     .with_website(Website::new("crates".into(), "crates.io".into(), "code".into()).unwrap());
+    /// let shared = HashSet::from(["github".to_owned()]);
+    ///
+    /// let order = WebsiteOrder::from([("fun".into(), vec!["crates".into(), "github".into()])]);
+    /// overlay.reorder(&order, &shared).unwrap();
+    /// assert_eq!(overlay.website("crates").unwrap().section(), "fun");
+    ///
+    /// let order = WebsiteOrder::from([("fun".into(), vec!["missing".into()])]);
+    /// assert!(overlay.reorder(&order, &shared).is_err());
+    /// ```
+    pub fn reorder(&mut self, order: &WebsiteOrder, shared: &HashSet<String>) -> HomescreenResult {
+        if order.values().flatten().any(|website_name| {
+            self.website(website_name).is_none() && !shared.contains(website_name)
+        }) {
+            return Err(ServerError::CannotFindWebsite.into());
+        }
+
+        for (section, website_names) in order {
+            self.moved.retain(|website_name, (moved_to, _)| {
+                moved_to != section || website_names.contains(website_name)
+            });
+
+            let mut unlisted: Vec<_> = self
+                .websites
+                .iter_mut()
+                .filter(|(website, _)| {
+                    website.section == *section && !website_names.contains(&website.website_name)
+                })
+                .collect();
+            unlisted.sort_by_key(|(_, position)| *position);
+            for (offset, (_, position)) in unlisted.into_iter().enumerate() {
+                *position = (website_names.len() + offset) as i32;
+            }
+
+            for (position, website_name) in website_names.iter().enumerate() {
+                match self
+                    .websites
+                    .iter_mut()
+                    .find(|(website, _)| website.website_name == *website_name)
+                {
+                    Some((website, personal_position)) => {
+                        website.section = section.clone();
+                        *personal_position = position as i32;
+                    }
+                    None => {
+                        self.moved
+                            .insert(website_name.clone(), (section.clone(), position as i32));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
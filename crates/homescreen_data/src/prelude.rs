@@ -1 +1,21 @@
-pub use crate::websites::{Website, WebsiteSection};
+#[cfg(feature = "poison_wasm")]
+pub use crate::store::{
+    memory::MemoryStore,
+    sql::{Dialect, SqlStore},
+    BackupStore, PersonalStore, SearchEngineStore, SectionStore, Store, SyncStore, TokenStore,
+    UserStore, VisitStore, WebsiteStore,
+};
+pub use crate::{
+    backup::{Backup, ImportStrategy, BACKUP_VERSION},
+    bookmarks::{Bookmark, ImportIssue, ImportReport},
+    personal::Overlay,
+    proxies::TrustedProxies,
+    search_engines::SearchEngine,
+    sections::{SectionWebsites, WebsiteSection},
+    sites::{SiteSection, Sites},
+    suggestions::{History, Suggestion},
+    tokens::{ApiToken, Scope},
+    users::{Caller, Role, User, MIN_PASSWORD_LENGTH},
+    visits::{DailyVisits, WebsiteSort, WebsiteStats},
+    websites::{Website, WebsiteChange, WebsiteOrder, WebsitePatch},
+};
@@ -0,0 +1,30 @@
+use ipnet::IpNet;
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+
+/// The address ranges reverse proxies connect from, only requests that come straight from one of
+/// them can say who is logged in with a header
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct TrustedProxies(Vec<IpNet>);
+impl TrustedProxies {
+    pub fn new(ranges: Vec<IpNet>) -> Self {
+        Self(ranges)
+    }
+    /// Whether requests from `address` come from a trusted proxy
+    ///
+    /// ```rust
+    /// # use homescreen_data::prelude::*;
+    /// let trusted = TrustedProxies::new(vec!["10.0.0.0/8".parse().unwrap(), "::1/128".parse().unwrap()]);
+    /// assert!(trusted.trusts("10.1.2.3".parse().unwrap()));
+    /// assert!(trusted.trusts("::ffff:10.1.2.3".parse().unwrap()));
+    /// assert!(trusted.trusts("::1".parse().unwrap()));
+    /// assert!(!trusted.trusts("192.168.1.2".parse().unwrap()));
+    /// assert!(!TrustedProxies::default().trusts("10.1.2.3".parse().unwrap()));
+    /// ```
+    pub fn trusts(&self, address: IpAddr) -> bool {
+        // Proxies connecting over IPv4 to a socket listening on IPv6 show up as mapped addresses
+        let address = address.to_canonical();
+        self.0.iter().any(|range| range.contains(&address))
+    }
+}
@@ -0,0 +1,125 @@
+use crate::{sections::WebsiteSection, websites::encode_segment};
+use homescreen_errors::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// What the search bar searches with, picked by typing the prefix in front of the search
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct SearchEngine {
+    pub(crate) name: String,
+    /// The url searched with, `{query}` is replaced with the search
+    pub(crate) url_template: String,
+    pub(crate) prefix: String,
+    /// Searches without a prefix use the default engine, only one engine can be the default
+    #[serde(default)]
+    pub(crate) is_default: bool,
+}
+impl SearchEngine {
+    pub fn new(name: String, url_template: String, prefix: String) -> HomescreenResult<Self> {
+        Ok(Self {
+            name,
+            url_template: Self::validate_url_template(url_template)?,
+            prefix: Self::validate_prefix(prefix)?,
+            is_default: false,
+        })
+    }
+    /// The engine searches used before any were added
+    pub fn duckduckgo() -> Self {
+        Self {
+            name: "DuckDuckGo".into(),
+            url_template: "https://duckduckgo.com/?q={query}".into(),
+            prefix: "!ddg".into(),
+            is_default: true,
+        }
+    }
+    pub fn with_default(mut self, is_default: bool) -> Self {
+        self.is_default = is_default;
+        self
+    }
+    /// Unlike website links the url is kept whole, it has to include where the search goes
+    ///
+    /// ```rust
+    /// # use homescreen_data::prelude::*;
+    /// assert!(SearchEngine::validate_url_template("https://crates.io/search?q={query}".into()).is_ok());
+    /// assert!(SearchEngine::validate_url_template("https://crates.io/search".into()).is_err());
+    /// assert!(SearchEngine::validate_url_template("crates.io/search?q={query}".into()).is_err());
+    /// ```
+    pub fn validate_url_template(url_template: String) -> HomescreenResult<String> {
+        let valid = (url_template.starts_with("https://") || url_template.starts_with("http://"))
+            && url_template.contains("{query}");
+
+        if valid {
+            Ok(url_template)
+        } else {
+            Err(ServerError::CannotParseSearchUrl.into())
+        }
+    }
+    /// Prefixes are `!` followed by something that follows the same rules as section slugs
+    ///
+    /// ```rust
+    /// # use homescreen_data::prelude::*;
+    /// assert!(SearchEngine::validate_prefix("!gh".into()).is_ok());
+    /// assert!(SearchEngine::validate_prefix("gh".into()).is_err());
+    /// assert!(SearchEngine::validate_prefix("!".into()).is_err());
+    /// assert!(SearchEngine::validate_prefix("!Git Hub".into()).is_err());
+    /// ```
+    pub fn validate_prefix(prefix: String) -> HomescreenResult<String> {
+        prefix
+            .strip_prefix('!')
+            .map(str::to_owned)
+            .and_then(|slug| WebsiteSection::validate_slug(slug).ok())
+            .map(|_| prefix)
+            .ok_or(ServerError::CannotParseSearchPrefix.into())
+    }
+    /// The engine whose prefix the search starts with alongside the rest of the search, `None`
+    /// when it doesn't start with any of their prefixes
+    ///
+    /// ```rust
+    /// # use homescreen_data::prelude::*;
+    /// let crates = SearchEngine::new(
+    ///     "Crates".into(),
+    ///     "https://crates.io/search?q={query}".into(),
+    ///     "!crates".into(),
+    /// )
+    /// .unwrap();
+    /// let engines = [SearchEngine::duckduckgo(), crates];
+    ///
+    /// let (engine, search) = SearchEngine::pick(&engines, "!crates serde json").unwrap();
+    /// assert_eq!(engine.name(), "Crates");
+    /// assert_eq!(search, "serde json");
+    /// assert!(SearchEngine::pick(&engines, "serde !crates").is_none());
+    /// ```
+    pub fn pick<'a>(engines: &'a [Self], search: &'a str) -> Option<(&'a Self, &'a str)> {
+        let search = search.trim_start();
+        let (prefix, rest) = search
+            .split_once(char::is_whitespace)
+            .unwrap_or((search, ""));
+
+        engines
+            .iter()
+            .find(|engine| engine.prefix == prefix)
+            .map(|engine| (engine, rest.trim()))
+    }
+    /// The url that searches for `search` with this engine
+    ///
+    /// ```rust
+    /// # use homescreen_data::prelude::*;
+    /// let link = SearchEngine::duckduckgo().search_link("rust & friends");
+    /// assert_eq!(link, "https://duckduckgo.com/?q=rust%20%26%20friends");
+    /// ```
+    pub fn search_link(&self, search: &str) -> String {
+        self.url_template
+            .replace("{query}", &encode_segment(search))
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn url_template(&self) -> &str {
+        &self.url_template
+    }
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+    pub fn is_default(&self) -> bool {
+        self.is_default
+    }
+}
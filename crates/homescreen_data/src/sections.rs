@@ -0,0 +1,200 @@
+use crate::websites::{Website, WebsiteOrder};
+use homescreen_errors::prelude::*;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "poison_wasm")]
+use sqlx::prelude::FromRow;
+use std::{cmp::Reverse, collections::HashMap};
+
+#[cfg_attr(feature = "poison_wasm", derive(FromRow))]
+#[derive(Deserialize, Serialize, Clone, Hash, PartialEq, Eq, Debug)]
+pub struct WebsiteSection {
+    /// Left out of backups that were written by hand, it is never read back in anyway
+    #[serde(default)]
+    pub(crate) section_id: i32,
+    pub(crate) display_name: String,
+    pub(crate) slug: String,
+    pub(crate) position: i32,
+}
+impl WebsiteSection {
+    /// Slugs are used in urls and html ids so they are limited to lowercase ascii letters, digits,
+    /// `-` and `_`
+    ///
+    /// ```rust
+    /// # use homescreen_data::prelude::*;
+    /// assert!(WebsiteSection::validate_slug("code".into()).is_ok());
+    /// assert!(WebsiteSection::validate_slug("video-editing".into()).is_ok());
+    /// assert!(WebsiteSection::validate_slug("".into()).is_err());
+    /// assert!(WebsiteSection::validate_slug("Fun Stuff".into()).is_err());
+    /// ```
+    pub fn validate_slug(slug: String) -> HomescreenResult<String> {
+        let valid = !slug.is_empty()
+            && slug.chars().all(|char| {
+                char.is_ascii_lowercase() || char.is_ascii_digit() || "-_".contains(char)
+            });
+
+        if valid {
+            Ok(slug)
+        } else {
+            Err(ServerError::CannotParseWebsiteSection.into())
+        }
+    }
+    /// Makes a valid slug out of a display name, anything that isn't allowed in a slug becomes `-`
+    ///
+    /// ```rust
+    /// # use homescreen_data::prelude::*;
+    /// assert_eq!(WebsiteSection::slug_from("Code"), "code");
+    /// assert_eq!(WebsiteSection::slug_from("Bookmarks bar / Rust!"), "bookmarks-bar-rust");
+    /// assert_eq!(WebsiteSection::slug_from("🦀"), "section");
+    /// ```
+    pub fn slug_from(display_name: &str) -> String {
+        let slug = display_name
+            .to_lowercase()
+            .split(|char: char| !(char.is_ascii_alphanumeric() || "-_".contains(char)))
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("-");
+
+        if slug.is_empty() {
+            "section".to_owned()
+        } else {
+            slug
+        }
+    }
+    pub fn id(&self) -> i32 {
+        self.section_id
+    }
+    pub fn display_name(&self) -> &str {
+        &self.display_name
+    }
+    pub fn slug(&self) -> &str {
+        &self.slug
+    }
+    pub fn position(&self) -> i32 {
+        self.position
+    }
+}
+
+/// A section alongside the websites that belong to it, this is what the frontend renders as a
+/// single column
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct SectionWebsites {
+    section: WebsiteSection,
+    websites: Box<[Website]>,
+}
+impl SectionWebsites {
+    pub fn new(section: WebsiteSection, websites: Box<[Website]>) -> Self {
+        Self { section, websites }
+    }
+    pub fn section(&self) -> &WebsiteSection {
+        &self.section
+    }
+    pub fn websites(&self) -> &[Website] {
+        &self.websites
+    }
+    /// Drops every website that doesn't have `tag`
+    pub fn retain_tagged(&mut self, tag: &str) {
+        self.websites = self
+            .websites
+            .iter()
+            .filter(|website| website.has_tag(tag))
+            .cloned()
+            .collect();
+    }
+    /// Drops every website that `query` doesn't fuzzy match, see [`Website::fuzzy_score`]
+    pub fn retain_matching(&mut self, query: &str) {
+        self.websites = self
+            .websites
+            .iter()
+            .filter(|website| website.fuzzy_score(query).is_some())
+            .cloned()
+            .collect();
+    }
+    /// Lists the most visited websites first, `visits` is keyed by website name and websites
+    /// that aren't in it count as never visited
+    pub fn sort_by_visits(&mut self, visits: &HashMap<String, u64>) {
+        self.websites.sort_by_key(|website| {
+            Reverse(visits.get(&website.website_name).copied().unwrap_or(0))
+        });
+    }
+    /// Moves the website named `website_name` into the section `slug`, in front of the website
+    /// named `before` or at the end of the section if there isn't one. The order of the sections
+    /// that changed is returned so it can be sent to the backend, or `None` if nothing was moved
+    pub fn move_website(
+        columns: &mut [Self],
+        website_name: &str,
+        slug: &str,
+        before: Option<&str>,
+    ) -> Option<WebsiteOrder> {
+        if before == Some(website_name) {
+            return None;
+        }
+
+        let target = columns
+            .iter()
+            .position(|column| column.section.slug == slug)?;
+        let (source, index) = columns.iter().enumerate().find_map(|(source, column)| {
+            let index = column
+                .websites
+                .iter()
+                .position(|website| website.website_name == website_name)?;
+            Some((source, index))
+        })?;
+
+        let mut websites = columns[source].websites.to_vec();
+        let mut website = websites.remove(index);
+        columns[source].websites = websites.into_boxed_slice();
+
+        website.section = slug.to_owned();
+        let mut websites = columns[target].websites.to_vec();
+        let index = before
+            .and_then(|before| {
+                websites
+                    .iter()
+                    .position(|website| website.website_name == before)
+            })
+            .unwrap_or(websites.len());
+        websites.insert(index, website);
+        columns[target].websites = websites.into_boxed_slice();
+
+        Some(
+            [source, target]
+                .into_iter()
+                .map(|changed| {
+                    let column = &columns[changed];
+                    let website_names = column.websites.iter();
+                    (
+                        column.section.slug.clone(),
+                        website_names
+                            .map(|website| website.website_name.clone())
+                            .collect(),
+                    )
+                })
+                .collect(),
+        )
+    }
+}
+
+#[cfg(feature = "poison_wasm")]
+impl SectionWebsites {
+    /// Pairs every section with the websites that belong to it, keeping the order of both
+    pub(crate) fn group(
+        sections: impl IntoIterator<Item = WebsiteSection>,
+        websites: impl IntoIterator<Item = Website>,
+    ) -> Box<[Self]> {
+        let mut grouped: HashMap<String, Vec<Website>> = HashMap::new();
+        for website in websites {
+            grouped
+                .entry(website.section.clone())
+                .or_default()
+                .push(website);
+        }
+
+        sections
+            .into_iter()
+            .map(|section| {
+                let websites = grouped.remove(section.slug()).unwrap_or_default();
+                Self::new(section, websites.into_boxed_slice())
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{sections::WebsiteSection, websites::Website};
+use homescreen_errors::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// A section declared in the config, sections are shown in the order they are declared
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct SiteSection {
+    display_name: String,
+    slug: String,
+}
+impl SiteSection {
+    pub fn new(display_name: String, slug: String) -> Self {
+        Self { display_name, slug }
+    }
+    pub fn display_name(&self) -> &str {
+        &self.display_name
+    }
+    pub fn slug(&self) -> &str {
+        &self.slug
+    }
+}
+
+/// The sections and websites declared in the config, the database is made to match these on
+/// startup. Anything added through the api is left alone
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug, Default)]
+pub struct Sites {
+    #[serde(default)]
+    sections: Vec<SiteSection>,
+    #[serde(default)]
+    websites: Vec<Website>,
+}
+impl Sites {
+    pub fn new(sections: Vec<SiteSection>, websites: Vec<Website>) -> Self {
+        Self { sections, websites }
+    }
+    /// Adds everything declared in `other` after what is already declared
+    pub fn extend(&mut self, other: Self) {
+        self.sections.extend(other.sections);
+        self.websites.extend(other.websites);
+    }
+    /// Checks everything the same way the forms are, every slug, name, link and alias may only be
+    /// declared once
+    pub fn validate(self) -> HomescreenResult<Self> {
+        let mut slugs = HashSet::new();
+        let sections = self
+            .sections
+            .into_iter()
+            .map(|section| {
+                let slug = WebsiteSection::validate_slug(section.slug)?;
+                if !slugs.insert(slug.clone()) {
+                    return Err(ConfigError::DuplicateSite(format!("Section {slug}")).into());
+                }
+                Ok(SiteSection { slug, ..section })
+            })
+            .collect::<HomescreenResult<_>>()?;
+
+        let mut names = HashSet::new();
+        let mut links = HashSet::new();
+        let mut aliases = HashSet::new();
+        let websites = self
+            .websites
+            .into_iter()
+            .map(|website| {
+                let website =
+                    Website::new(website.website_name, website.website_link, website.section)?
+                        .with_tags(website.tags)?
+                        .with_alias(website.alias)?
+                        .with_link_template(website.link_template)?;
+                if !names.insert(website.name().to_owned()) {
+                    return Err(
+                        ConfigError::DuplicateSite(format!("Website {}", website.name())).into(),
+                    );
+                }
+                if !links.insert(website.link().to_owned()) {
+                    return Err(
+                        ConfigError::DuplicateSite(format!("Link {}", website.link())).into(),
+                    );
+                }
+                if let Some(alias) = website.alias() {
+                    if !aliases.insert(alias.to_owned()) {
+                        return Err(ConfigError::DuplicateSite(format!("Alias {alias}")).into());
+                    }
+                }
+                Ok(website)
+            })
+            .collect::<HomescreenResult<_>>()?;
+
+        Ok(Self { sections, websites })
+    }
+    pub fn sections(&self) -> &[SiteSection] {
+        &self.sections
+    }
+    pub fn websites(&self) -> &[Website] {
+        &self.websites
+    }
+}
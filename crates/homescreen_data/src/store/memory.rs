@@ -0,0 +1,1134 @@
+use crate::prelude::*;
+use chrono::{DateTime, NaiveDate, Utc};
+use homescreen_errors::prelude::*;
+use log::{error, info, warn};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+#[derive(Clone, Default)]
+struct MemoryData {
+    sections: Vec<WebsiteSection>,
+    websites: Vec<Website>,
+    next_section_id: i32,
+    /// Slugs of the sections declared in the config
+    managed_sections: HashSet<String>,
+    /// Names of the websites declared in the config
+    managed_websites: HashSet<String>,
+    /// The number of visits to each website on each day and when the last of them was
+    visits: HashMap<String, BTreeMap<NaiveDate, (u64, DateTime<Utc>)>>,
+    /// Kept in the order they were added
+    search_engines: Vec<SearchEngine>,
+    /// Every api token alongside its hash
+    tokens: Vec<(ApiToken, String)>,
+    /// Every user alongside their password hash, kept in the order they were added
+    users: Vec<(User, String)>,
+    /// The user each account at an identity provider belongs to, keyed by the issuer and subject
+    external_users: HashMap<(String, String), String>,
+    /// The user each session belongs to and when it expires, keyed by the session hash
+    sessions: HashMap<String, (String, DateTime<Utc>)>,
+    /// The personal homescreen of each user, keyed by their name
+    overlays: HashMap<String, Overlay>,
+}
+impl MemoryData {
+    fn sorted_sections(&self) -> Vec<WebsiteSection> {
+        let mut sections = self.sections.clone();
+        sections.sort_by_key(|section| (section.position, section.section_id));
+        sections
+    }
+    fn section(&self, slug: &str) -> HomescreenResult<&WebsiteSection> {
+        self.sections
+            .iter()
+            .find(|section| section.slug == slug)
+            .ok_or(ServerError::CannotFindSection.into())
+    }
+    fn website_index(&self, website_name: &str) -> Option<usize> {
+        self.websites
+            .iter()
+            .position(|website| website.website_name == website_name)
+    }
+    /// Websites are kept in display order, so one that moves to another section goes to the end
+    /// of it
+    fn replace_website(&mut self, index: usize, website: Website) {
+        if self.websites[index].section == website.section {
+            self.websites[index] = website;
+        } else {
+            self.websites.remove(index);
+            self.websites.push(website);
+        }
+    }
+    /// Creates the website, or updates the one with the same name when `replace` is set, see
+    /// [`WebsiteStore::create_or_update_website`]
+    fn save_website(&mut self, website: Website, replace: bool) -> HomescreenResult<WebsiteChange> {
+        let existing = self.website_index(&website.website_name);
+        if existing.is_some() && !replace
+            || existing.is_none() && self.personal_website_exists(&website.website_name)
+        {
+            return Err(ServerError::WebsiteAlreadyExists.into());
+        }
+        self.check_website(&website, Some(&website.website_name))?;
+
+        match existing {
+            Some(index) => {
+                self.replace_website(index, website);
+                Ok(WebsiteChange::Updated)
+            }
+            None => {
+                self.websites.push(website);
+                Ok(WebsiteChange::Created)
+            }
+        }
+    }
+    /// Checks the same constraints the database schema enforces before `website` is written over
+    /// the website named `replacing`
+    fn check_website(&self, website: &Website, replacing: Option<&str>) -> HomescreenResult {
+        self.section(&website.section)?;
+
+        let link_taken = self.websites.iter().any(|existing| {
+            existing.website_link == website.website_link
+                && Some(existing.website_name.as_str()) != replacing
+        });
+        if link_taken {
+            return Err(ServerError::WebsiteLinkAlreadyExists.into());
+        }
+        if self.alias_owner(website, replacing).is_some() {
+            return Err(ServerError::WebsiteAliasAlreadyExists.into());
+        }
+
+        Ok(())
+    }
+    /// The website other than the one named `replacing` that already uses the alias of `website`
+    fn alias_owner(&self, website: &Website, replacing: Option<&str>) -> Option<&Website> {
+        website.alias.as_ref().and_then(|alias| {
+            self.websites.iter().find(|existing| {
+                existing.alias.as_ref() == Some(alias)
+                    && Some(existing.website_name.as_str()) != replacing
+            })
+        })
+    }
+    /// Whether anyone has a personal website called `website_name`, see `SqlStore`
+    fn personal_website_exists(&self, website_name: &str) -> bool {
+        self.overlays
+            .values()
+            .any(|overlay| overlay.website(website_name).is_some())
+    }
+    /// Whether anything is still in the section, which includes personal websites and shared
+    /// websites someone moved there on their own homescreen
+    fn section_in_use(&self, slug: &str) -> bool {
+        self.websites.iter().any(|website| website.section == slug)
+            || self.overlays.values().any(|overlay| {
+                overlay
+                    .websites
+                    .iter()
+                    .any(|(website, _)| website.section == slug)
+                    || overlay.moved.values().any(|(section, _)| section == slug)
+            })
+    }
+    /// Drops everything from the personal homescreens that refers to a user, website or section
+    /// that no longer exists, the same as the database schema cascades deletes
+    fn prune_overlays(&mut self) {
+        let users: HashSet<&str> = self.users.iter().map(|(user, _)| user.name()).collect();
+        let websites: HashSet<&str> = self.websites.iter().map(Website::name).collect();
+        let sections: HashSet<&str> = self.sections.iter().map(WebsiteSection::slug).collect();
+
+        self.overlays
+            .retain(|owner, _| users.contains(owner.as_str()));
+        for overlay in self.overlays.values_mut() {
+            overlay
+                .websites
+                .retain(|(website, _)| sections.contains(website.section()));
+            overlay
+                .hidden
+                .retain(|website_name| websites.contains(website_name.as_str()));
+            overlay.moved.retain(|website_name, (section, _)| {
+                websites.contains(website_name.as_str()) && sections.contains(section.as_str())
+            });
+        }
+    }
+    /// Puts the websites in `order` first in each section, see [`WebsiteStore::reorder_websites`]
+    fn order_websites(&mut self, order: WebsiteOrder) -> HomescreenResult {
+        for (section, website_names) in &order {
+            self.section(section)?;
+            if let Some(missing) = website_names
+                .iter()
+                .find(|website_name| self.website_index(website_name).is_none())
+            {
+                return Err(ServerError::CannotFindWebsite)
+                    .inspect_err(|_| error!("Cannot reorder websites, cannot find {missing}"))?;
+            }
+        }
+
+        // Moving the websites of a section to the end of the list in their new order leaves every
+        // other section untouched
+        for (section, website_names) in order {
+            let (mut listed, rest): (Vec<_>, Vec<_>) = self
+                .websites
+                .drain(..)
+                .partition(|website| website_names.contains(&website.website_name));
+            let (unlisted, rest): (Vec<_>, Vec<_>) = rest
+                .into_iter()
+                .partition(|website| website.section == section);
+
+            listed.sort_by_key(|website| {
+                website_names
+                    .iter()
+                    .position(|website_name| *website_name == website.website_name)
+            });
+            listed
+                .iter_mut()
+                .for_each(|website| website.section = section.clone());
+
+            self.websites = rest;
+            self.websites.extend(listed);
+            self.websites.extend(unlisted);
+        }
+
+        Ok(())
+    }
+}
+
+/// Keeps everything in memory, nothing is persisted between runs so this is mostly useful for
+/// testing
+#[derive(Clone, Default)]
+pub struct MemoryStore {
+    data: Arc<Mutex<MemoryData>>,
+}
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    fn data(&self) -> MutexGuard<'_, MemoryData> {
+        // Every write is checked before anything is changed so the data is still consistent even
+        // if another thread panicked while holding the lock
+        self.data
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl WebsiteStore for MemoryStore {
+    async fn get_websites(&self) -> HomescreenResult<Box<[SectionWebsites]>> {
+        let data = self.data();
+
+        Ok(SectionWebsites::group(
+            data.sorted_sections(),
+            data.websites.iter().cloned(),
+        ))
+    }
+    async fn get_section_websites(&self, section: &str) -> HomescreenResult<Box<[Website]>> {
+        Ok(self
+            .data()
+            .websites
+            .iter()
+            .filter(|website| website.section == section)
+            .cloned()
+            .collect())
+    }
+    async fn create_or_update_website(&self, website: Website) -> HomescreenResult<WebsiteChange> {
+        self.data().save_website(website, true)
+    }
+    async fn create_website(&self, website: Website) -> HomescreenResult {
+        self.data().save_website(website, false).map(|_| ())
+    }
+    async fn update_website(
+        &self,
+        website_name: &str,
+        patch: WebsitePatch,
+    ) -> HomescreenResult<Website> {
+        let mut data = self.data();
+        let index = data
+            .website_index(website_name)
+            .ok_or(ServerError::CannotFindWebsite)?;
+        let website = patch.apply(data.websites[index].clone())?;
+
+        if website.website_name != website_name
+            && (data.website_index(&website.website_name).is_some()
+                || data.personal_website_exists(&website.website_name))
+        {
+            return Err(ServerError::WebsiteAlreadyExists.into());
+        }
+        data.check_website(&website, Some(website_name))?;
+
+        if data.managed_websites.remove(website_name) {
+            data.managed_websites.insert(website.website_name.clone());
+        }
+        if let Some(visits) = data.visits.remove(website_name) {
+            data.visits.insert(website.website_name.clone(), visits);
+        }
+        for overlay in data.overlays.values_mut() {
+            if overlay.hidden.remove(website_name) {
+                overlay.hidden.insert(website.website_name.clone());
+            }
+            if let Some(moved) = overlay.moved.remove(website_name) {
+                overlay.moved.insert(website.website_name.clone(), moved);
+            }
+        }
+        data.replace_website(index, website.clone());
+        Ok(website)
+    }
+    async fn delete_website(&self, website_name: &str) -> HomescreenResult {
+        let mut data = self.data();
+        let index = data
+            .website_index(website_name)
+            .ok_or(ServerError::CannotDeleteExistingWebsite)?;
+
+        data.websites.remove(index);
+        data.managed_websites.remove(website_name);
+        data.visits.remove(website_name);
+        data.prune_overlays();
+        Ok(())
+    }
+    async fn reorder_websites(&self, order: WebsiteOrder) -> HomescreenResult {
+        self.data().order_websites(order)
+    }
+}
+
+impl SectionStore for MemoryStore {
+    async fn get_sections(&self) -> HomescreenResult<Box<[WebsiteSection]>> {
+        Ok(self.data().sorted_sections().into_boxed_slice())
+    }
+    async fn get_section(&self, slug: &str) -> HomescreenResult<WebsiteSection> {
+        self.data().section(slug).cloned()
+    }
+    async fn create_section(
+        &self,
+        display_name: String,
+        slug: String,
+        position: Option<i32>,
+    ) -> HomescreenResult<WebsiteSection> {
+        let slug = WebsiteSection::validate_slug(slug)?;
+
+        let mut data = self.data();
+        if data.section(&slug).is_ok() {
+            return Err(ServerError::SectionAlreadyExists.into());
+        }
+
+        let position = position.unwrap_or_else(|| {
+            data.sections
+                .iter()
+                .map(|section| section.position + 1)
+                .max()
+                .unwrap_or(0)
+        });
+        data.next_section_id += 1;
+
+        let section = WebsiteSection {
+            section_id: data.next_section_id,
+            display_name,
+            slug,
+            position,
+        };
+        data.sections.push(section.clone());
+
+        Ok(section)
+    }
+    async fn update_section(
+        &self,
+        slug: &str,
+        display_name: Option<String>,
+        new_slug: Option<String>,
+        position: Option<i32>,
+    ) -> HomescreenResult<WebsiteSection> {
+        let new_slug = new_slug.map(WebsiteSection::validate_slug).transpose()?;
+
+        let mut data = self.data();
+        data.section(slug)?;
+        if let Some(new_slug) = new_slug.as_deref() {
+            if new_slug != slug && data.section(new_slug).is_ok() {
+                return Err(ServerError::SectionAlreadyExists.into());
+            }
+        }
+
+        let section = data
+            .sections
+            .iter_mut()
+            .find(|section| section.slug == slug)
+            .ok_or(ServerError::CannotFindSection)?;
+        if let Some(display_name) = display_name {
+            section.display_name = display_name;
+        }
+        if let Some(position) = position {
+            section.position = position;
+        }
+        if let Some(new_slug) = new_slug {
+            section.slug = new_slug;
+        }
+        let section = section.clone();
+
+        if data.managed_sections.remove(slug) {
+            data.managed_sections.insert(section.slug.clone());
+        }
+
+        for website in data
+            .websites
+            .iter_mut()
+            .filter(|website| website.section == slug)
+        {
+            website.section = section.slug.clone();
+        }
+        for overlay in data.overlays.values_mut() {
+            for (website, _) in &mut overlay.websites {
+                if website.section == slug {
+                    website.section = section.slug.clone();
+                }
+            }
+            for (moved_to, _) in overlay.moved.values_mut() {
+                if moved_to == slug {
+                    *moved_to = section.slug.clone();
+                }
+            }
+        }
+
+        Ok(section)
+    }
+    async fn delete_section(&self, slug: &str) -> HomescreenResult {
+        let mut data = self.data();
+        data.section(slug)?;
+
+        if data.section_in_use(slug) {
+            return Err(ServerError::SectionIsNotEmpty.into());
+        }
+
+        data.sections.retain(|section| section.slug != slug);
+        data.managed_sections.remove(slug);
+        data.prune_overlays();
+        Ok(())
+    }
+}
+
+impl BackupStore for MemoryStore {
+    async fn import_backup(&self, backup: Backup, strategy: ImportStrategy) -> HomescreenResult {
+        let backup = backup.validate()?;
+        let overwrite = strategy != ImportStrategy::MergeKeepExisting;
+
+        let mut data = self.data();
+        // Personal websites keep the sections missing from the backup from being deleted, the
+        // same as the database schema refuses it
+        if strategy == ImportStrategy::Replace {
+            let missing = data.overlays.values().any(|overlay| {
+                overlay.websites.iter().any(|(website, _)| {
+                    !backup
+                        .sections()
+                        .iter()
+                        .any(|section| section.slug == website.section)
+                })
+            });
+            if missing {
+                return Err(ServerError::SectionIsNotEmpty.into());
+            }
+        }
+        // Everything is applied to a copy so nothing changes if any part of the backup fails
+        let mut imported = match strategy {
+            // Only the sections and websites are replaced, the same as in the database. Visits and
+            // personal homescreens are kept for the websites and sections that are in the backup
+            ImportStrategy::Replace => MemoryData {
+                sections: Vec::new(),
+                websites: Vec::new(),
+                managed_sections: HashSet::new(),
+                managed_websites: HashSet::new(),
+                ..data.clone()
+            },
+            _ => data.clone(),
+        };
+
+        for section in backup.sections() {
+            match imported
+                .sections
+                .iter_mut()
+                .find(|existing| existing.slug == section.slug)
+            {
+                Some(existing) if overwrite => {
+                    existing.display_name = section.display_name.clone();
+                    existing.position = section.position;
+                }
+                Some(_) => {}
+                None => {
+                    imported.next_section_id += 1;
+                    imported.sections.push(WebsiteSection {
+                        section_id: imported.next_section_id,
+                        ..section.clone()
+                    });
+                }
+            }
+        }
+
+        for website in backup.websites() {
+            let index = imported.website_index(&website.website_name);
+            if index.is_some() && !overwrite {
+                continue;
+            }
+            let personal =
+                index.is_none() && imported.personal_website_exists(&website.website_name);
+            match (personal, overwrite) {
+                (true, true) => return Err(ServerError::WebsiteAlreadyExists.into()),
+                (true, false) => continue,
+                _ => {}
+            }
+
+            let link_taken = imported.websites.iter().any(|existing| {
+                existing.website_link == website.website_link
+                    && existing.website_name != website.website_name
+            });
+            match (link_taken, overwrite) {
+                (true, true) => return Err(ServerError::WebsiteLinkAlreadyExists.into()),
+                (true, false) => continue,
+                _ => {}
+            }
+            let alias_taken = imported
+                .alias_owner(website, Some(&website.website_name))
+                .is_some();
+            match (alias_taken, overwrite) {
+                (true, true) => return Err(ServerError::WebsiteAliasAlreadyExists.into()),
+                (true, false) => continue,
+                _ => {}
+            }
+            imported.check_website(website, Some(&website.website_name))?;
+
+            match index {
+                Some(index) => imported.replace_website(index, website.clone()),
+                None => imported.websites.push(website.clone()),
+            }
+        }
+
+        if strategy == ImportStrategy::Replace {
+            imported.visits.retain(|website_name, _| {
+                imported
+                    .websites
+                    .iter()
+                    .any(|website| website.website_name == *website_name)
+            });
+        }
+
+        if backup.has_search_engines() {
+            if strategy == ImportStrategy::Replace {
+                imported.search_engines.clear();
+            }
+            for engine in backup.search_engines() {
+                let index = imported
+                    .search_engines
+                    .iter()
+                    .position(|existing| existing.name == engine.name);
+                if index.is_some() && !overwrite {
+                    continue;
+                }
+
+                let prefix_taken = imported.search_engines.iter().any(|existing| {
+                    existing.prefix == engine.prefix && existing.name != engine.name
+                });
+                match (prefix_taken, overwrite) {
+                    (true, true) => return Err(ServerError::SearchPrefixAlreadyExists.into()),
+                    (true, false) => continue,
+                    _ => {}
+                }
+
+                // Merging in a new default engine doesn't take over from the one already in use
+                let has_default = imported
+                    .search_engines
+                    .iter()
+                    .any(|existing| existing.is_default);
+                let engine = engine
+                    .clone()
+                    .with_default(engine.is_default && (overwrite || !has_default));
+                if engine.is_default {
+                    imported
+                        .search_engines
+                        .iter_mut()
+                        .for_each(|existing| existing.is_default = false);
+                }
+                match index {
+                    Some(index) => imported.search_engines[index] = engine,
+                    None => imported.search_engines.push(engine),
+                }
+            }
+        }
+
+        imported.prune_overlays();
+        *data = imported;
+        Ok(())
+    }
+    async fn import_bookmarks(
+        &self,
+        bookmarks: Vec<Bookmark>,
+        dry_run: bool,
+    ) -> HomescreenResult<ImportReport> {
+        let mut data = self.data();
+        let personal = data
+            .overlays
+            .values()
+            .flat_map(|overlay| {
+                overlay
+                    .websites()
+                    .map(|website| website.website_name.clone())
+            })
+            .collect();
+        let (report, sections) = ImportReport::plan(
+            bookmarks,
+            data.websites
+                .iter()
+                .map(|website| (website.website_name.clone(), website.website_link.clone())),
+            personal,
+            data.sections
+                .iter()
+                .map(|section| section.slug.clone())
+                .collect(),
+            dry_run,
+        );
+        if dry_run {
+            return Ok(report);
+        }
+
+        for (display_name, slug) in sections {
+            let position = data
+                .sections
+                .iter()
+                .map(|section| section.position + 1)
+                .max()
+                .unwrap_or(0);
+            data.next_section_id += 1;
+            let section_id = data.next_section_id;
+            data.sections.push(WebsiteSection {
+                section_id,
+                display_name,
+                slug,
+                position,
+            });
+        }
+        data.websites
+            .extend(report.imported_websites().iter().cloned());
+
+        Ok(report)
+    }
+}
+
+impl SyncStore for MemoryStore {
+    async fn sync_sites(&self, sites: Sites) -> HomescreenResult {
+        let sites = sites.validate()?;
+        let declared_section = |slug: &str| {
+            sites
+                .sections()
+                .iter()
+                .any(|section| section.slug() == slug)
+        };
+        let declared_website = |name: &str| {
+            sites
+                .websites()
+                .iter()
+                .any(|website| website.name() == name)
+        };
+
+        let mut data = self.data();
+        // Everything is applied to a copy so nothing changes if any part of the sync fails
+        let mut synced = data.clone();
+
+        // Websites that are no longer declared go first so their names and links are free again
+        let stale: Vec<String> = synced
+            .managed_websites
+            .iter()
+            .filter(|website_name| !declared_website(website_name))
+            .cloned()
+            .collect();
+        for website_name in stale {
+            synced
+                .websites
+                .retain(|website| website.website_name != website_name);
+            synced.managed_websites.remove(&website_name);
+            synced.visits.remove(&website_name);
+            info!("Deleted website {website_name}, it is no longer declared");
+        }
+
+        for (position, declared) in sites.sections().iter().enumerate() {
+            match synced
+                .sections
+                .iter_mut()
+                .find(|section| section.slug == declared.slug())
+            {
+                Some(section) => {
+                    section.display_name = declared.display_name().to_owned();
+                    section.position = position as i32;
+                }
+                None => {
+                    synced.next_section_id += 1;
+                    synced.sections.push(WebsiteSection {
+                        section_id: synced.next_section_id,
+                        display_name: declared.display_name().to_owned(),
+                        slug: declared.slug().to_owned(),
+                        position: position as i32,
+                    });
+                    info!("Created section {}", declared.slug());
+                }
+            }
+            synced.managed_sections.insert(declared.slug().to_owned());
+        }
+
+        let mut order = WebsiteOrder::new();
+        for website in sites.websites() {
+            if let Some(owner) = synced.websites.iter().find(|existing| {
+                existing.website_link == website.website_link
+                    && existing.website_name != website.website_name
+            }) {
+                return Err(ConfigError::SiteLinkAlreadyUsed(
+                    website.website_name.clone(),
+                    owner.website_name.clone(),
+                ))
+                .inspect_err(|err| error!("Cannot sync sites, {err}"))?;
+            }
+            if let Some(owner) = synced.alias_owner(website, Some(&website.website_name)) {
+                return Err(ConfigError::SiteAliasAlreadyUsed(
+                    website.website_name.clone(),
+                    owner.website_name.clone(),
+                ))
+                .inspect_err(|err| error!("Cannot sync sites, {err}"))?;
+            }
+            if synced.website_index(&website.website_name).is_none()
+                && synced.personal_website_exists(&website.website_name)
+            {
+                return Err(ConfigError::SiteNameUsedByPersonalWebsite(
+                    website.website_name.clone(),
+                ))
+                .inspect_err(|err| error!("Cannot sync sites, {err}"))?;
+            }
+            synced.check_website(website, Some(&website.website_name))?;
+
+            match synced.website_index(&website.website_name) {
+                Some(index) => synced.replace_website(index, website.clone()),
+                None => {
+                    synced.websites.push(website.clone());
+                    info!("Created website {}", website.website_name);
+                }
+            }
+            synced.managed_websites.insert(website.website_name.clone());
+
+            order
+                .entry(website.section.clone())
+                .or_default()
+                .push(website.website_name.clone());
+        }
+        // Declared websites come first in their section in the order they are declared
+        synced.order_websites(order)?;
+
+        let stale: Vec<String> = synced
+            .managed_sections
+            .iter()
+            .filter(|slug| !declared_section(slug))
+            .cloned()
+            .collect();
+        for slug in stale {
+            if synced.section_in_use(&slug) {
+                warn!(
+                    "Section {slug} is no longer declared but is kept since it still has websites"
+                );
+                continue;
+            }
+
+            synced.sections.retain(|section| section.slug != slug);
+            synced.managed_sections.remove(&slug);
+            info!("Deleted section {slug}, it is no longer declared");
+        }
+
+        // Sections that aren't declared keep their order after the declared ones
+        let undeclared: Vec<String> = synced
+            .sorted_sections()
+            .into_iter()
+            .map(|section| section.slug)
+            .filter(|slug| !declared_section(slug))
+            .collect();
+        for (position, slug) in undeclared.iter().enumerate() {
+            if let Some(section) = synced
+                .sections
+                .iter_mut()
+                .find(|section| section.slug == *slug)
+            {
+                section.position = (sites.sections().len() + position) as i32;
+            }
+        }
+
+        synced.prune_overlays();
+        *data = synced;
+        Ok(())
+    }
+}
+
+impl VisitStore for MemoryStore {
+    async fn record_visit(
+        &self,
+        website_name: &str,
+        visited_at: DateTime<Utc>,
+    ) -> HomescreenResult<Website> {
+        let mut data = self.data();
+        // Names are looked up first so an alias can never hide another website's name
+        let website = data
+            .website_index(website_name)
+            .map(|index| &data.websites[index])
+            .or_else(|| {
+                data.websites
+                    .iter()
+                    .find(|website| website.alias.as_deref() == Some(website_name))
+            })
+            .cloned()
+            .ok_or(ServerError::CannotFindWebsite)?;
+
+        let (visits, last_visit) = data
+            .visits
+            .entry(website.website_name.clone())
+            .or_default()
+            .entry(visited_at.date_naive())
+            .or_insert((0, visited_at));
+        *visits += 1;
+        *last_visit = visited_at;
+
+        Ok(website)
+    }
+    async fn website_stats(&self, website_name: &str) -> HomescreenResult<WebsiteStats> {
+        let data = self.data();
+        data.website_index(website_name)
+            .ok_or(ServerError::CannotFindWebsite)?;
+
+        let days = data.visits.get(website_name).cloned().unwrap_or_default();
+        Ok(WebsiteStats::new(
+            website_name.to_owned(),
+            days.values().map(|(_, last_visit)| *last_visit).max(),
+            days.into_iter()
+                .map(|(day, (visits, _))| DailyVisits::new(day, visits))
+                .collect(),
+        ))
+    }
+    async fn visit_counts(&self) -> HomescreenResult<HashMap<String, u64>> {
+        Ok(self
+            .data()
+            .visits
+            .iter()
+            .map(|(website_name, days)| {
+                (
+                    website_name.clone(),
+                    days.values().map(|(visits, _)| visits).sum(),
+                )
+            })
+            .collect())
+    }
+}
+
+impl SearchEngineStore for MemoryStore {
+    async fn get_search_engines(&self) -> HomescreenResult<Box<[SearchEngine]>> {
+        Ok(self.data().search_engines.clone().into_boxed_slice())
+    }
+    async fn create_or_update_search_engine(&self, engine: SearchEngine) -> HomescreenResult {
+        let mut data = self.data();
+
+        let prefix_taken = data
+            .search_engines
+            .iter()
+            .any(|existing| existing.prefix == engine.prefix && existing.name != engine.name);
+        if prefix_taken {
+            return Err(ServerError::SearchPrefixAlreadyExists.into());
+        }
+
+        if engine.is_default {
+            data.search_engines
+                .iter_mut()
+                .for_each(|existing| existing.is_default = false);
+        }
+        match data
+            .search_engines
+            .iter_mut()
+            .find(|existing| existing.name == engine.name)
+        {
+            Some(existing) => *existing = engine,
+            None => data.search_engines.push(engine),
+        }
+
+        Ok(())
+    }
+    async fn delete_search_engine(&self, name: &str) -> HomescreenResult {
+        let mut data = self.data();
+        let index = data
+            .search_engines
+            .iter()
+            .position(|engine| engine.name == name)
+            .ok_or(ServerError::CannotFindSearchEngine)?;
+
+        data.search_engines.remove(index);
+        Ok(())
+    }
+}
+
+impl TokenStore for MemoryStore {
+    async fn get_tokens(&self) -> HomescreenResult<Box<[ApiToken]>> {
+        let data = self.data();
+        Ok(data.tokens.iter().map(|(token, _)| token.clone()).collect())
+    }
+    async fn create_token(&self, token: ApiToken, token_hash: String) -> HomescreenResult {
+        let mut data = self.data();
+        if data
+            .tokens
+            .iter()
+            .any(|(existing, _)| existing.name == token.name)
+        {
+            return Err(ServerError::TokenAlreadyExists.into());
+        }
+
+        data.tokens.push((token, token_hash));
+        Ok(())
+    }
+    async fn find_token(&self, token_hash: &str) -> HomescreenResult<Option<ApiToken>> {
+        let data = self.data();
+        Ok(data
+            .tokens
+            .iter()
+            .find(|(_, hash)| hash == token_hash)
+            .map(|(token, _)| token.clone()))
+    }
+    async fn delete_token(&self, name: &str) -> HomescreenResult {
+        let mut data = self.data();
+        let index = data
+            .tokens
+            .iter()
+            .position(|(token, _)| token.name == name)
+            .ok_or(ServerError::CannotFindToken)?;
+
+        data.tokens.remove(index);
+        Ok(())
+    }
+}
+
+impl UserStore for MemoryStore {
+    async fn get_users(&self) -> HomescreenResult<Box<[User]>> {
+        let data = self.data();
+        Ok(data.users.iter().map(|(user, _)| user.clone()).collect())
+    }
+    async fn create_or_update_user(
+        &self,
+        user: User,
+        password_hash: String,
+    ) -> HomescreenResult<bool> {
+        let mut data = self.data();
+        match data
+            .users
+            .iter_mut()
+            .find(|(existing, _)| existing.name == user.name)
+        {
+            Some((existing, existing_hash)) => {
+                existing.role = user.role;
+                *existing_hash = password_hash;
+                Ok(false)
+            }
+            None => {
+                data.users.push((user, password_hash));
+                Ok(true)
+            }
+        }
+    }
+    async fn create_or_update_external_user(
+        &self,
+        user: User,
+        issuer: &str,
+        subject: &str,
+    ) -> HomescreenResult<User> {
+        let mut data = self.data();
+        let account = (issuer.to_owned(), subject.to_owned());
+        if let Some(name) = data.external_users.get(&account).cloned() {
+            let (linked, _) = data
+                .users
+                .iter_mut()
+                .find(|(existing, _)| existing.name == name)
+                .ok_or(ServerError::CannotFindUser)?;
+            linked.role = user.role;
+            return Ok(linked.clone());
+        }
+
+        let already_linked = data.external_users.values().any(|name| *name == user.name);
+        let existing = data
+            .users
+            .iter_mut()
+            .find(|(existing, _)| existing.name == user.name);
+        match existing {
+            // Users from an identity provider were kept by name alone before, see `SqlStore`
+            Some((existing, password_hash)) if password_hash.is_empty() && !already_linked => {
+                existing.role = user.role;
+                let linked = existing.clone();
+                data.external_users.insert(account, linked.name.clone());
+                Ok(linked)
+            }
+            Some(_) => {
+                warn!(
+                    "Refused to link {} to an account at {issuer}, the name is taken",
+                    user.name
+                );
+                Err(ServerError::UserAlreadyExists(user.name).into())
+            }
+            None => {
+                data.external_users.insert(account, user.name.clone());
+                data.users.push((user.clone(), String::new()));
+                Ok(user)
+            }
+        }
+    }
+    async fn find_user(&self, name: &str) -> HomescreenResult<Option<(User, String)>> {
+        let data = self.data();
+        Ok(data
+            .users
+            .iter()
+            .find(|(user, _)| user.name == name)
+            .cloned())
+    }
+    async fn delete_user(&self, name: &str) -> HomescreenResult {
+        let mut data = self.data();
+        let index = data
+            .users
+            .iter()
+            .position(|(user, _)| user.name == name)
+            .ok_or(ServerError::CannotFindUser)?;
+
+        data.users.remove(index);
+        data.external_users.retain(|_, user_name| user_name != name);
+        data.sessions.retain(|_, (user_name, _)| user_name != name);
+        data.prune_overlays();
+        Ok(())
+    }
+    async fn create_session(
+        &self,
+        session_hash: String,
+        name: &str,
+        expires_at: DateTime<Utc>,
+    ) -> HomescreenResult {
+        let mut data = self.data();
+        if !data.users.iter().any(|(user, _)| user.name == name) {
+            return Err(ServerError::CannotFindUser.into());
+        }
+
+        data.sessions
+            .insert(session_hash, (name.to_owned(), expires_at));
+        Ok(())
+    }
+    async fn find_session(
+        &self,
+        session_hash: &str,
+        now: DateTime<Utc>,
+    ) -> HomescreenResult<Option<User>> {
+        let data = self.data();
+        let Some((name, expires_at)) = data.sessions.get(session_hash) else {
+            return Ok(None);
+        };
+        if *expires_at <= now {
+            return Ok(None);
+        }
+
+        Ok(data
+            .users
+            .iter()
+            .find(|(user, _)| &user.name == name)
+            .map(|(user, _)| user.clone()))
+    }
+    async fn delete_session(&self, session_hash: &str) -> HomescreenResult {
+        self.data().sessions.remove(session_hash);
+        Ok(())
+    }
+}
+
+impl PersonalStore for MemoryStore {
+    async fn get_overlay(&self, owner: &str) -> HomescreenResult<Overlay> {
+        Ok(self.data().overlays.get(owner).cloned().unwrap_or_default())
+    }
+    async fn create_or_update_personal_website(
+        &self,
+        owner: &str,
+        website: Website,
+    ) -> HomescreenResult<WebsiteChange> {
+        let mut data = self.data();
+        data.section(&website.section)?;
+        if !data.users.iter().any(|(user, _)| user.name == owner) {
+            return Err(ServerError::CannotFindUser.into());
+        }
+        if data.website_index(&website.website_name).is_some() {
+            return Err(ServerError::WebsiteAlreadyExists.into());
+        }
+
+        let overlay = data.overlays.entry(owner.to_owned()).or_default();
+        let existing = overlay
+            .websites
+            .iter()
+            .position(|(existing, _)| existing.website_name == website.website_name);
+        // Personal websites keep their place unless they move to another section, the same as
+        // shared ones
+        match existing {
+            Some(index) if overlay.websites[index].0.section == website.section => {
+                let position = overlay.websites[index].1;
+                let updated = Overlay::new().with_website(website).websites.remove(0).0;
+                overlay.websites[index] = (updated, position);
+                Ok(WebsiteChange::Updated)
+            }
+            Some(index) => {
+                overlay.websites.remove(index);
+                *overlay = std::mem::take(overlay).with_website(website);
+                Ok(WebsiteChange::Updated)
+            }
+            None => {
+                *overlay = std::mem::take(overlay).with_website(website);
+                Ok(WebsiteChange::Created)
+            }
+        }
+    }
+    async fn delete_personal_website(&self, owner: &str, website_name: &str) -> HomescreenResult {
+        let mut data = self.data();
+        let overlay = data
+            .overlays
+            .get_mut(owner)
+            .ok_or(ServerError::CannotFindWebsite)?;
+        let index = overlay
+            .websites
+            .iter()
+            .position(|(website, _)| website.website_name == website_name)
+            .ok_or(ServerError::CannotFindWebsite)?;
+
+        overlay.websites.remove(index);
+        Ok(())
+    }
+    async fn set_website_hidden(
+        &self,
+        owner: &str,
+        website_name: &str,
+        hidden: bool,
+    ) -> HomescreenResult {
+        let mut data = self.data();
+        if data.website_index(website_name).is_none() {
+            return Err(ServerError::CannotFindWebsite.into());
+        }
+        if !data.users.iter().any(|(user, _)| user.name == owner) {
+            return Err(ServerError::CannotFindUser.into());
+        }
+
+        let overlay = data.overlays.entry(owner.to_owned()).or_default();
+        if hidden {
+            overlay.hidden.insert(website_name.to_owned());
+        } else {
+            overlay.hidden.remove(website_name);
+        }
+        Ok(())
+    }
+    async fn reorder_personal_websites(
+        &self,
+        owner: &str,
+        order: WebsiteOrder,
+    ) -> HomescreenResult {
+        let mut data = self.data();
+        for section in order.keys() {
+            data.section(section)?;
+        }
+        if !data.users.iter().any(|(user, _)| user.name == owner) {
+            return Err(ServerError::CannotFindUser.into());
+        }
+
+        let shared = data
+            .websites
+            .iter()
+            .map(|website| website.website_name.clone())
+            .collect();
+        let mut overlay = data.overlays.get(owner).cloned().unwrap_or_default();
+        overlay
+            .reorder(&order, &shared)
+            .inspect_err(|err| error!("Cannot reorder personal websites of {owner}, {err}"))?;
+
+        data.overlays.insert(owner.to_owned(), overlay);
+        Ok(())
+    }
+}
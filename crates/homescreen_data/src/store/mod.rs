@@ -0,0 +1,259 @@
+pub(crate) mod memory;
+pub(crate) mod sql;
+
+use crate::prelude::*;
+use chrono::{DateTime, Utc};
+use homescreen_errors::prelude::*;
+use std::{collections::HashMap, future::Future};
+
+/// Persistence for websites, implemented once per kind of database
+pub trait WebsiteStore {
+    /// Every section in order, each alongside the websites that belong to it
+    fn get_websites(&self)
+        -> impl Future<Output = HomescreenResult<Box<[SectionWebsites]>>> + Send;
+    fn get_section_websites(
+        &self,
+        section: &str,
+    ) -> impl Future<Output = HomescreenResult<Box<[Website]>>> + Send;
+    /// Creates the website if there is none with the same name, otherwise updates the link,
+    /// section and tags of the existing one
+    fn create_or_update_website(
+        &self,
+        website: Website,
+    ) -> impl Future<Output = HomescreenResult<WebsiteChange>> + Send;
+    /// Creates the website, refusing with [`ServerError::WebsiteAlreadyExists`] if there is one
+    /// with the same name rather than changing it
+    fn create_website(&self, website: Website) -> impl Future<Output = HomescreenResult> + Send;
+    /// Changes whatever `patch` sets on an existing website, renames must never leave the website
+    /// missing or duplicated
+    fn update_website(
+        &self,
+        website_name: &str,
+        patch: WebsitePatch,
+    ) -> impl Future<Output = HomescreenResult<Website>> + Send;
+    fn delete_website(&self, website_name: &str) -> impl Future<Output = HomescreenResult> + Send;
+    /// Puts the listed websites at the start of each section in the given order, moving them into
+    /// that section if they are elsewhere. Websites that are not listed keep their order after the
+    /// listed ones, either every change is made or none are
+    fn reorder_websites(
+        &self,
+        order: WebsiteOrder,
+    ) -> impl Future<Output = HomescreenResult> + Send;
+}
+
+/// Persistence for sections, implemented once per kind of database
+pub trait SectionStore {
+    fn get_sections(&self) -> impl Future<Output = HomescreenResult<Box<[WebsiteSection]>>> + Send;
+    fn get_section(
+        &self,
+        slug: &str,
+    ) -> impl Future<Output = HomescreenResult<WebsiteSection>> + Send;
+    /// Creates a new section, when no position is given it is placed after every existing section
+    fn create_section(
+        &self,
+        display_name: String,
+        slug: String,
+        position: Option<i32>,
+    ) -> impl Future<Output = HomescreenResult<WebsiteSection>> + Send;
+    /// Updates any of the display name, slug or position of a section, websites follow the section
+    /// when its slug is changed
+    fn update_section(
+        &self,
+        slug: &str,
+        display_name: Option<String>,
+        new_slug: Option<String>,
+        position: Option<i32>,
+    ) -> impl Future<Output = HomescreenResult<WebsiteSection>> + Send;
+    /// Deletes a section, fails if any websites still belong to it
+    fn delete_section(&self, slug: &str) -> impl Future<Output = HomescreenResult> + Send;
+}
+
+/// Restoring backups and importing bookmarks, implemented once per kind of database
+pub trait BackupStore {
+    /// Applies a backup using `strategy`, either all of it is applied or none of it is
+    fn import_backup(
+        &self,
+        backup: Backup,
+        strategy: ImportStrategy,
+    ) -> impl Future<Output = HomescreenResult> + Send;
+    /// Turns bookmarks into websites, creating a section for each folder that doesn't have one
+    /// yet. Either every website in the report is imported or none are, and nothing is written
+    /// when `dry_run` is set
+    fn import_bookmarks(
+        &self,
+        bookmarks: Vec<Bookmark>,
+        dry_run: bool,
+    ) -> impl Future<Output = HomescreenResult<ImportReport>> + Send;
+}
+
+/// Keeping the database in line with the sites declared in the config, implemented once per kind
+/// of database
+pub trait SyncStore {
+    /// Creates, updates and deletes sections and websites until the ones managed by the config
+    /// match `sites`, anything added through the api is left alone unless it is declared in
+    /// `sites` too. Either every change is made or none are
+    fn sync_sites(&self, sites: Sites) -> impl Future<Output = HomescreenResult> + Send;
+}
+
+/// Counting visits to websites, implemented once per kind of database
+pub trait VisitStore {
+    /// Counts a visit to the website at `visited_at` and returns the website that was visited,
+    /// `website_name` may also be an alias but a website with that name comes first
+    fn record_visit(
+        &self,
+        website_name: &str,
+        visited_at: DateTime<Utc>,
+    ) -> impl Future<Output = HomescreenResult<Website>> + Send;
+    fn website_stats(
+        &self,
+        website_name: &str,
+    ) -> impl Future<Output = HomescreenResult<WebsiteStats>> + Send;
+    /// How often every website has been visited in total, keyed by website name
+    fn visit_counts(&self) -> impl Future<Output = HomescreenResult<HashMap<String, u64>>> + Send;
+}
+
+/// Persistence for the search engines the search bar uses, implemented once per kind of database
+pub trait SearchEngineStore {
+    /// Every search engine in the order they were added
+    fn get_search_engines(
+        &self,
+    ) -> impl Future<Output = HomescreenResult<Box<[SearchEngine]>>> + Send;
+    /// Creates the search engine if there is none with the same name, otherwise updates the
+    /// existing one. When it is the default every other engine stops being the default
+    fn create_or_update_search_engine(
+        &self,
+        engine: SearchEngine,
+    ) -> impl Future<Output = HomescreenResult> + Send;
+    fn delete_search_engine(&self, name: &str) -> impl Future<Output = HomescreenResult> + Send;
+}
+
+/// Api tokens, implemented once per kind of database
+pub trait TokenStore {
+    fn get_tokens(&self) -> impl Future<Output = HomescreenResult<Box<[ApiToken]>>> + Send;
+    /// Stores `token` under the hash of what callers send, see [`ApiToken::hash`]
+    fn create_token(
+        &self,
+        token: ApiToken,
+        token_hash: String,
+    ) -> impl Future<Output = HomescreenResult> + Send;
+    /// The token whose hash is `token_hash`, `None` if there isn't one
+    fn find_token(
+        &self,
+        token_hash: &str,
+    ) -> impl Future<Output = HomescreenResult<Option<ApiToken>>> + Send;
+    fn delete_token(&self, name: &str) -> impl Future<Output = HomescreenResult> + Send;
+}
+
+/// Users and the sessions of the browsers they have logged in from, implemented once per kind of
+/// database
+pub trait UserStore {
+    fn get_users(&self) -> impl Future<Output = HomescreenResult<Box<[User]>>> + Send;
+    /// Creates `user`, or replaces the role and password of the user with the same name, returns
+    /// whether it was created. See [`User::hash_password`] for `password_hash`
+    fn create_or_update_user(
+        &self,
+        user: User,
+        password_hash: String,
+    ) -> impl Future<Output = HomescreenResult<bool>> + Send;
+    /// Creates `user` without a password for the account `subject` at the identity provider
+    /// `issuer` so they can only log in through it, or changes the role of the user already linked
+    /// to that account, returns the user they log in as. A user with the same name is only linked
+    /// to the account if they came from an identity provider before accounts were kept, anyone
+    /// else is refused with [`ServerError::UserAlreadyExists`]
+    fn create_or_update_external_user(
+        &self,
+        user: User,
+        issuer: &str,
+        subject: &str,
+    ) -> impl Future<Output = HomescreenResult<User>> + Send;
+    /// The user called `name` alongside their password hash, `None` if there isn't one
+    fn find_user(
+        &self,
+        name: &str,
+    ) -> impl Future<Output = HomescreenResult<Option<(User, String)>>> + Send;
+    /// Deletes the user called `name` along with every one of their sessions
+    fn delete_user(&self, name: &str) -> impl Future<Output = HomescreenResult> + Send;
+    /// Stores a session for the user called `name` under the hash of the secret in its cookie
+    fn create_session(
+        &self,
+        session_hash: String,
+        name: &str,
+        expires_at: DateTime<Utc>,
+    ) -> impl Future<Output = HomescreenResult> + Send;
+    /// The user the session whose hash is `session_hash` belongs to, `None` if there isn't one or
+    /// it expired before `now`
+    fn find_session(
+        &self,
+        session_hash: &str,
+        now: DateTime<Utc>,
+    ) -> impl Future<Output = HomescreenResult<Option<User>>> + Send;
+    /// Deletes the session, doing nothing if it has already gone
+    fn delete_session(&self, session_hash: &str) -> impl Future<Output = HomescreenResult> + Send;
+}
+
+/// Everyone's personal homescreen layered over the shared one, implemented once per kind of
+/// database. Deleting a user, a shared website or a section deletes whatever refers to it
+pub trait PersonalStore {
+    /// The personal websites, hidden websites and moved websites of `owner`
+    fn get_overlay(&self, owner: &str) -> impl Future<Output = HomescreenResult<Overlay>> + Send;
+    /// Creates the personal website if `owner` has none with the same name, otherwise updates the
+    /// link and section of theirs. Fails if a shared website already has the same name
+    fn create_or_update_personal_website(
+        &self,
+        owner: &str,
+        website: Website,
+    ) -> impl Future<Output = HomescreenResult<WebsiteChange>> + Send;
+    fn delete_personal_website(
+        &self,
+        owner: &str,
+        website_name: &str,
+    ) -> impl Future<Output = HomescreenResult> + Send;
+    /// Hides the shared website from `owner` or shows it again
+    fn set_website_hidden(
+        &self,
+        owner: &str,
+        website_name: &str,
+        hidden: bool,
+    ) -> impl Future<Output = HomescreenResult> + Send;
+    /// Changes the order `owner` sees, see [`Overlay::reorder`]. Either every change is made or
+    /// none are
+    fn reorder_personal_websites(
+        &self,
+        owner: &str,
+        order: WebsiteOrder,
+    ) -> impl Future<Output = HomescreenResult> + Send;
+}
+
+/// Everything the backend needs from a database
+pub trait Store:
+    WebsiteStore
+    + SectionStore
+    + BackupStore
+    + SyncStore
+    + VisitStore
+    + SearchEngineStore
+    + TokenStore
+    + UserStore
+    + PersonalStore
+    + Clone
+    + Send
+    + Sync
+    + 'static
+{
+}
+impl<T> Store for T where
+    T: WebsiteStore
+        + SectionStore
+        + BackupStore
+        + SyncStore
+        + VisitStore
+        + SearchEngineStore
+        + TokenStore
+        + UserStore
+        + PersonalStore
+        + Clone
+        + Send
+        + Sync
+        + 'static
+{
+}
@@ -0,0 +1,2481 @@
+use crate::prelude::*;
+use chrono::{DateTime, NaiveDate, Utc};
+use homescreen_errors::prelude::*;
+use log::{error, info, trace, warn};
+use sqlx::{
+    any::{install_default_drivers, AnyPoolOptions},
+    error::ErrorKind,
+    migrate::{Migrate, MigrateDatabase, MigrateError, Migrator},
+    Any, AnyConnection, AnyPool,
+};
+use std::{
+    borrow::Cow,
+    collections::{BTreeSet, HashMap},
+};
+
+/// The columns a [`Website`] is read from. The Any driver can't decode a `NULL` string so a
+/// missing alias or link template is read as an empty one, see [`clear_empty_columns`]
+const WEBSITE_COLUMNS: &str = "website_name, website_link, section, COALESCE(alias, '') AS alias, COALESCE(link_template, '') AS link_template";
+
+/// Turns the empty strings [`WEBSITE_COLUMNS`] reads missing values as back into `None`
+fn clear_empty_columns(website: &mut Website) {
+    website.alias = website.alias.take().filter(|alias| !alias.is_empty());
+    website.link_template = website
+        .link_template
+        .take()
+        .filter(|link_template| !link_template.is_empty());
+}
+
+/// The kinds of database that [`SqlStore`] can talk to, the queries are shared between them
+/// except where their syntax differs
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Dialect {
+    MySql,
+    Sqlite,
+    Postgres,
+}
+impl Dialect {
+    /// Picks the dialect from the scheme of the url
+    ///
+    /// ```rust
+    /// # use homescreen_data::prelude::*;
+    /// assert_eq!(Dialect::from_url("mysql://localhost/homescreen").unwrap(), Dialect::MySql);
+    /// assert_eq!(Dialect::from_url("sqlite://homescreen.db").unwrap(), Dialect::Sqlite);
+    /// assert_eq!(Dialect::from_url("postgres://localhost/homescreen").unwrap(), Dialect::Postgres);
+    /// assert!(Dialect::from_url("homescreen.db").is_err());
+    /// ```
+    pub fn from_url(database_url: &str) -> HomescreenResult<Self> {
+        match database_url.split_once(':').map(|(scheme, _)| scheme) {
+            Some("mysql" | "mariadb") => Ok(Self::MySql),
+            Some("sqlite") => Ok(Self::Sqlite),
+            Some("postgres" | "postgresql") => Ok(Self::Postgres),
+            _ => Err(StartupError::UnsupportedDatabaseUrl(database_url.to_string()).into()),
+        }
+    }
+    /// Locks the selected rows until the end of the transaction, SQLite has no row locks since it
+    /// only ever allows a single writer
+    fn for_update(self) -> &'static str {
+        match self {
+            Self::MySql | Self::Postgres => " FOR UPDATE",
+            Self::Sqlite => "",
+        }
+    }
+    /// Queries are written with `?` placeholders, Postgres numbers its placeholders instead
+    ///
+    /// ```rust
+    /// # use homescreen_data::prelude::*;
+    /// let query = "UPDATE websites SET section = ? WHERE website_name = ?";
+    /// assert_eq!(Dialect::Sqlite.sql(query), query);
+    /// assert_eq!(
+    ///     Dialect::Postgres.sql(query),
+    ///     "UPDATE websites SET section = $1 WHERE website_name = $2"
+    /// );
+    /// ```
+    pub fn sql(self, query: &str) -> Cow<'_, str> {
+        match self {
+            Self::MySql | Self::Sqlite => Cow::Borrowed(query),
+            Self::Postgres => {
+                let mut placeholders = 0;
+                let query =
+                    query
+                        .split('?')
+                        .fold(String::with_capacity(query.len()), |mut sql, part| {
+                            if placeholders > 0 {
+                                sql.push_str(&format!("${placeholders}"));
+                            }
+                            placeholders += 1;
+                            sql.push_str(part);
+                            sql
+                        });
+                Cow::Owned(query)
+            }
+        }
+    }
+    /// Adds a tag to the tags table unless it is already there
+    fn insert_tag(self) -> &'static str {
+        match self {
+            Self::MySql => "INSERT IGNORE INTO tags(name) VALUES(?)",
+            Self::Sqlite => "INSERT OR IGNORE INTO tags(name) VALUES(?)",
+            Self::Postgres => "INSERT INTO tags(name) VALUES(?) ON CONFLICT DO NOTHING",
+        }
+    }
+    /// Adds a visit to the count for a website on a day, creating the count if it is the first
+    fn record_visit(self) -> &'static str {
+        match self {
+            Self::MySql => "INSERT INTO website_visits(website_name, day, visits, last_visit) VALUES(?, ?, 1, ?) ON DUPLICATE KEY UPDATE visits = visits + 1, last_visit = VALUES(last_visit)",
+            Self::Sqlite | Self::Postgres => "INSERT INTO website_visits(website_name, day, visits, last_visit) VALUES(?, ?, 1, ?) ON CONFLICT(website_name, day) DO UPDATE SET visits = website_visits.visits + 1, last_visit = excluded.last_visit",
+        }
+    }
+    /// The migrations that create and update the schema, each dialect has its own since the
+    /// column types differ between them
+    fn migrator(self) -> Migrator {
+        match self {
+            Self::MySql => sqlx::migrate!("migrations/mysql"),
+            Self::Sqlite => sqlx::migrate!("migrations/sqlite"),
+            Self::Postgres => sqlx::migrate!("migrations/postgres"),
+        }
+    }
+}
+
+/// Stores everything in a SQL database, see [`Dialect`] for the ones that are supported
+#[derive(Clone)]
+pub struct SqlStore {
+    database: AnyPool,
+    dialect: Dialect,
+}
+impl SqlStore {
+    /// Connects to the database at `database_url`, SQLite databases are created if they do not
+    /// exist yet. The schema is not touched until [`SqlStore::migrate`] is called
+    pub async fn connect(database_url: &str) -> HomescreenResult<Self> {
+        let dialect = Dialect::from_url(database_url)?;
+        install_default_drivers();
+
+        let connection_error =
+            |err| StartupError::CannotConnectToDatabase(err, database_url.to_string());
+
+        let in_memory = dialect == Dialect::Sqlite && database_url.contains(":memory:");
+        let exists = in_memory || Any::database_exists(database_url).await.unwrap_or(false);
+
+        if dialect == Dialect::Sqlite && !exists {
+            info!("Creating database {database_url}");
+            Any::create_database(database_url)
+                .await
+                .map_err(connection_error)?;
+        }
+
+        // Every connection to an in-memory SQLite database gets its own empty database, so there
+        // can only be one and it has to be kept alive
+        let options = if in_memory {
+            AnyPoolOptions::new()
+                .max_connections(1)
+                .idle_timeout(None)
+                .max_lifetime(None)
+        } else {
+            AnyPoolOptions::new()
+        };
+
+        let database = options
+            .connect(database_url)
+            .await
+            .map_err(connection_error)
+            .inspect(|_| trace!("Connected to database"))?;
+
+        Ok(Self { database, dialect })
+    }
+    /// Applies every migration that the database is missing, nothing is changed if the database
+    /// has been migrated by a newer version of the backend
+    pub async fn migrate(&self) -> HomescreenResult {
+        let migrator = self.dialect.migrator();
+        let mut connection = self
+            .database
+            .acquire()
+            .await
+            .map_err(MigrateError::Execute)
+            .map_err(StartupError::CannotMigrateDatabase)?;
+
+        connection
+            .ensure_migrations_table()
+            .await
+            .map_err(StartupError::CannotMigrateDatabase)?;
+        let applied = connection
+            .list_applied_migrations()
+            .await
+            .map_err(StartupError::CannotMigrateDatabase)?;
+
+        let latest = migrator
+            .iter()
+            .map(|migration| migration.version)
+            .max()
+            .unwrap_or_default();
+        let database_version = applied
+            .iter()
+            .map(|migration| migration.version)
+            .max()
+            .unwrap_or_default();
+        if database_version > latest {
+            return Err(StartupError::DatabaseSchemaTooNew(database_version, latest).into());
+        }
+
+        migrator
+            .iter()
+            .filter(|migration| {
+                !applied
+                    .iter()
+                    .any(|applied| applied.version == migration.version)
+            })
+            .for_each(|migration| {
+                info!(
+                    "Applying migration {} {}",
+                    migration.version, migration.description
+                )
+            });
+
+        migrator
+            .run_direct(&mut *connection)
+            .await
+            .map_err(StartupError::CannotMigrateDatabase)
+            .inspect(|_| trace!("Database is at schema version {latest}"))
+            .map_err(HomescreenError::from)
+    }
+    pub fn database(&self) -> &AnyPool {
+        &self.database
+    }
+    pub fn dialect(&self) -> Dialect {
+        self.dialect
+    }
+    fn map_website_write_error(err: sqlx::Error) -> ServerError {
+        match err.as_database_error().map(|err| err.kind()) {
+            Some(ErrorKind::ForeignKeyViolation) => ServerError::CannotFindSection,
+            Some(ErrorKind::UniqueViolation) => ServerError::WebsiteLinkAlreadyExists,
+            _ => ServerError::CannotInsertWebsite(err),
+        }
+    }
+    async fn next_section_position(&self) -> HomescreenResult<i32> {
+        sqlx::query_scalar(
+            &self
+                .dialect
+                .sql("SELECT COALESCE(MAX(position) + 1, 0) FROM sections"),
+        )
+        .fetch_one(&self.database)
+        .await
+        .map(|position: i64| position as i32)
+        .map_err(ServerError::CannotRetrieveSections)
+        .map_err(HomescreenError::from)
+        .inspect_err(|err| error!("Cannot retrieve section positions, {err}"))
+    }
+    /// The position that puts a website after every other website in `section`
+    async fn next_website_position(
+        &self,
+        section: &str,
+        connection: &mut AnyConnection,
+    ) -> Result<i32, sqlx::Error> {
+        sqlx::query_scalar(
+            &self
+                .dialect
+                .sql("SELECT COALESCE(MAX(position) + 1, 0) FROM websites WHERE section = ?"),
+        )
+        .bind(section)
+        .fetch_one(connection)
+        .await
+        .map(|position: i64| position as i32)
+    }
+    /// Fills in the tags of every website in `websites`
+    async fn load_tags(
+        &self,
+        websites: &mut [Website],
+        connection: &mut AnyConnection,
+    ) -> Result<(), sqlx::Error> {
+        let tagged: Vec<(String, String)> = sqlx::query_as(&self.dialect.sql(
+            "SELECT website_tags.website_name, tags.name FROM website_tags JOIN tags ON tags.tag_id = website_tags.tag_id",
+        ))
+        .fetch_all(connection)
+        .await?;
+
+        let mut tags: HashMap<String, BTreeSet<String>> = HashMap::new();
+        for (website_name, tag) in tagged {
+            tags.entry(website_name).or_default().insert(tag);
+        }
+        for website in websites {
+            website.tags = tags.remove(&website.website_name).unwrap_or_default();
+        }
+
+        Ok(())
+    }
+    async fn website_tags(
+        &self,
+        website_name: &str,
+        connection: &mut AnyConnection,
+    ) -> Result<BTreeSet<String>, sqlx::Error> {
+        sqlx::query_scalar(&self.dialect.sql(
+            "SELECT tags.name FROM website_tags JOIN tags ON tags.tag_id = website_tags.tag_id WHERE website_tags.website_name = ?",
+        ))
+        .bind(website_name)
+        .fetch_all(connection)
+        .await
+        .map(BTreeSet::from_iter)
+    }
+    /// Replaces the tags of a website, tags that no website uses anymore are removed
+    async fn set_website_tags(
+        &self,
+        website_name: &str,
+        tags: &BTreeSet<String>,
+        connection: &mut AnyConnection,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            &self
+                .dialect
+                .sql("DELETE FROM website_tags WHERE website_name = ?"),
+        )
+        .bind(website_name)
+        .execute(&mut *connection)
+        .await?;
+
+        for tag in tags {
+            sqlx::query(&self.dialect.sql(self.dialect.insert_tag()))
+                .bind(tag)
+                .execute(&mut *connection)
+                .await?;
+            sqlx::query(&self.dialect.sql(
+                "INSERT INTO website_tags(website_name, tag_id) SELECT ?, tag_id FROM tags WHERE name = ?",
+            ))
+            .bind(website_name)
+            .bind(tag)
+            .execute(&mut *connection)
+            .await?;
+        }
+
+        Self::delete_unused_tags(connection).await
+    }
+    /// Puts the websites in `order` first in each section, see [`WebsiteStore::reorder_websites`]
+    async fn order_websites(
+        &self,
+        order: &WebsiteOrder,
+        connection: &mut AnyConnection,
+    ) -> HomescreenResult {
+        for (section, website_names) in order {
+            let unlisted: Vec<String> = sqlx::query_scalar(&self.dialect.sql(
+                "SELECT website_name FROM websites WHERE section = ? ORDER BY position, website_name",
+            ))
+            .bind(section)
+            .fetch_all(&mut *connection)
+            .await
+            .map_err(ServerError::CannotReorderWebsites)?
+            .into_iter()
+            .filter(|website_name| !website_names.contains(website_name))
+            .collect();
+
+            for (position, website_name) in website_names.iter().chain(&unlisted).enumerate() {
+                let moved =
+                    sqlx::query(&self.dialect.sql(
+                        "UPDATE websites SET section = ?, position = ? WHERE website_name = ?",
+                    ))
+                    .bind(section)
+                    .bind(position as i32)
+                    .bind(website_name)
+                    .execute(&mut *connection)
+                    .await
+                    .map_err(|err| match err.as_database_error().map(|err| err.kind()) {
+                        Some(ErrorKind::ForeignKeyViolation) => ServerError::CannotFindSection,
+                        _ => ServerError::CannotReorderWebsites(err),
+                    })
+                    .map_err(HomescreenError::from)
+                    .inspect_err(|err| error!("Cannot reorder websites, {err}"))?;
+
+                if moved.rows_affected() == 0 {
+                    return Err(ServerError::CannotFindWebsite.into());
+                }
+            }
+        }
+
+        Ok(())
+    }
+    /// The personal homescreen of `owner`, read on `connection` so it can be part of a transaction
+    async fn overlay(
+        &self,
+        owner: &str,
+        connection: &mut AnyConnection,
+    ) -> Result<Overlay, sqlx::Error> {
+        let websites: Vec<(String, String, String, i32)> = sqlx::query_as(&self.dialect.sql(
+            "SELECT website_name, website_link, section, position FROM personal_websites \
+             WHERE owner = ? ORDER BY position, website_name",
+        ))
+        .bind(owner)
+        .fetch_all(&mut *connection)
+        .await?;
+        let hidden: Vec<String> = sqlx::query_scalar(
+            &self
+                .dialect
+                .sql("SELECT website_name FROM hidden_websites WHERE owner = ?"),
+        )
+        .bind(owner)
+        .fetch_all(&mut *connection)
+        .await?;
+        let moved: Vec<(String, String, i32)> = sqlx::query_as(
+            &self
+                .dialect
+                .sql("SELECT website_name, section, position FROM moved_websites WHERE owner = ?"),
+        )
+        .bind(owner)
+        .fetch_all(&mut *connection)
+        .await?;
+
+        Ok(Overlay {
+            websites: websites
+                .into_iter()
+                .map(|(website_name, website_link, section, position)| {
+                    let website = Website {
+                        website_name,
+                        website_link,
+                        section,
+                        tags: BTreeSet::new(),
+                        alias: None,
+                        link_template: None,
+                        personal: true,
+                    };
+                    (website, position)
+                })
+                .collect(),
+            hidden: hidden.into_iter().collect(),
+            moved: moved
+                .into_iter()
+                .map(|(website_name, section, position)| (website_name, (section, position)))
+                .collect(),
+        })
+    }
+    /// Whether anyone has a personal website called `website_name`, shared websites can't take
+    /// the name since both would show on their homescreen
+    async fn personal_website_exists(
+        &self,
+        website_name: &str,
+        connection: &mut AnyConnection,
+    ) -> Result<bool, sqlx::Error> {
+        sqlx::query(
+            &self
+                .dialect
+                .sql("SELECT owner FROM personal_websites WHERE website_name = ?"),
+        )
+        .bind(website_name)
+        .fetch_optional(connection)
+        .await
+        .map(|row| row.is_some())
+    }
+    /// Whether anything is still in the section, which includes personal websites and shared
+    /// websites someone moved there on their own homescreen
+    async fn section_in_use(
+        &self,
+        slug: &str,
+        connection: &mut AnyConnection,
+    ) -> Result<bool, sqlx::Error> {
+        for query in [
+            "SELECT website_name FROM websites WHERE section = ?",
+            "SELECT website_name FROM personal_websites WHERE section = ?",
+            "SELECT website_name FROM moved_websites WHERE section = ?",
+        ] {
+            let in_use = sqlx::query(&self.dialect.sql(query))
+                .bind(slug)
+                .fetch_optional(&mut *connection)
+                .await?
+                .is_some();
+            if in_use {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+    /// Adds the search engines of a backup the same way its websites are added, engines that
+    /// already exist are matched by name
+    async fn import_search_engines(
+        &self,
+        engines: &[SearchEngine],
+        strategy: ImportStrategy,
+        connection: &mut AnyConnection,
+    ) -> HomescreenResult {
+        let overwrite = strategy != ImportStrategy::MergeKeepExisting;
+
+        if strategy == ImportStrategy::Replace {
+            sqlx::query("DELETE FROM search_engines")
+                .execute(&mut *connection)
+                .await
+                .map_err(ServerError::CannotImportBackup)?;
+        }
+
+        for engine in engines {
+            let exists = sqlx::query(
+                &self
+                    .dialect
+                    .sql("SELECT name FROM search_engines WHERE name = ?"),
+            )
+            .bind(engine.name())
+            .fetch_optional(&mut *connection)
+            .await
+            .map_err(ServerError::CannotImportBackup)?
+            .is_some();
+            if exists && !overwrite {
+                continue;
+            }
+
+            // Checked up front since a failed query would abort the whole transaction on Postgres
+            let prefix_taken = sqlx::query(
+                &self
+                    .dialect
+                    .sql("SELECT name FROM search_engines WHERE prefix = ? AND name <> ?"),
+            )
+            .bind(engine.prefix())
+            .bind(engine.name())
+            .fetch_optional(&mut *connection)
+            .await
+            .map_err(ServerError::CannotImportBackup)?
+            .is_some();
+            match (prefix_taken, overwrite) {
+                (true, true) => return Err(ServerError::SearchPrefixAlreadyExists.into()),
+                (true, false) => continue,
+                _ => {}
+            }
+
+            // Merging in a new default engine doesn't take over from the one already in use
+            let has_default = sqlx::query(
+                &self
+                    .dialect
+                    .sql("SELECT name FROM search_engines WHERE is_default = ?"),
+            )
+            .bind(true)
+            .fetch_optional(&mut *connection)
+            .await
+            .map_err(ServerError::CannotImportBackup)?
+            .is_some();
+            let is_default = engine.is_default() && (overwrite || !has_default);
+            if is_default {
+                sqlx::query(&self.dialect.sql("UPDATE search_engines SET is_default = ?"))
+                    .bind(false)
+                    .execute(&mut *connection)
+                    .await
+                    .map_err(ServerError::CannotImportBackup)?;
+            }
+
+            let query = if exists {
+                "UPDATE search_engines SET url_template = ?, prefix = ?, is_default = ? WHERE name = ?"
+            } else {
+                "INSERT INTO search_engines(url_template, prefix, is_default, name) VALUES(?, ?, ?, ?)"
+            };
+            sqlx::query(&self.dialect.sql(query))
+                .bind(engine.url_template())
+                .bind(engine.prefix())
+                .bind(is_default)
+                .bind(engine.name())
+                .execute(&mut *connection)
+                .await
+                .map_err(ServerError::CannotImportBackup)
+                .map_err(HomescreenError::from)
+                .inspect_err(|err| {
+                    error!("Cannot import search engine {}, {err}", engine.name())
+                })?;
+        }
+
+        Ok(())
+    }
+    /// Creates the website, or updates the one with the same name when `replace` is set, see
+    /// [`WebsiteStore::create_or_update_website`]
+    async fn save_website(
+        &self,
+        website: Website,
+        replace: bool,
+    ) -> HomescreenResult<WebsiteChange> {
+        let mut transaction = self
+            .database
+            .begin()
+            .await
+            .map_err(ServerError::CannotInsertWebsite)?;
+
+        let query = format!(
+            "SELECT section, position FROM websites WHERE website_name = ?{}",
+            self.dialect.for_update()
+        );
+        let existing: Option<(String, i32)> = sqlx::query_as(&self.dialect.sql(&query))
+            .bind(&website.website_name)
+            .fetch_optional(&mut *transaction)
+            .await
+            .map_err(ServerError::CannotInsertWebsite)?;
+
+        if existing.is_some() && !replace {
+            return Err(ServerError::WebsiteAlreadyExists.into());
+        }
+        if existing.is_none() {
+            let personal = self
+                .personal_website_exists(&website.website_name, &mut transaction)
+                .await
+                .map_err(ServerError::CannotInsertWebsite)?;
+            if personal {
+                return Err(ServerError::WebsiteAlreadyExists.into());
+            }
+        }
+        let alias_taken = self
+            .alias_owner(website.alias(), &website.website_name, &mut transaction)
+            .await
+            .map_err(ServerError::CannotInsertWebsite)?
+            .is_some();
+        if alias_taken {
+            return Err(ServerError::WebsiteAliasAlreadyExists.into());
+        }
+
+        // Websites keep their place unless they move to another section, then they go at the end
+        let (query, change, position) = match existing {
+            Some((section, position)) if section == website.section => (
+                "UPDATE websites SET website_link = ?, section = ?, position = ?, alias = ?, link_template = ? WHERE website_name = ?",
+                WebsiteChange::Updated,
+                position,
+            ),
+            Some(_) => (
+                "UPDATE websites SET website_link = ?, section = ?, position = ?, alias = ?, link_template = ? WHERE website_name = ?",
+                WebsiteChange::Updated,
+                self.next_website_position(&website.section, &mut transaction)
+                    .await
+                    .map_err(ServerError::CannotInsertWebsite)?,
+            ),
+            None => (
+                "INSERT INTO websites(website_link, section, position, alias, link_template, website_name) VALUES(?, ?, ?, ?, ?, ?)",
+                WebsiteChange::Created,
+                self.next_website_position(&website.section, &mut transaction)
+                    .await
+                    .map_err(ServerError::CannotInsertWebsite)?,
+            ),
+        };
+
+        sqlx::query(&self.dialect.sql(query))
+            .bind(&website.website_link)
+            .bind(&website.section)
+            .bind(position)
+            .bind(&website.alias)
+            .bind(&website.link_template)
+            .bind(&website.website_name)
+            .execute(&mut *transaction)
+            .await
+            .map_err(Self::map_website_write_error)
+            .map_err(HomescreenError::from)
+            .inspect_err(|err| error!("Cannot insert website, {err}"))?;
+
+        self.set_website_tags(&website.website_name, &website.tags, &mut transaction)
+            .await
+            .map_err(ServerError::CannotInsertWebsite)
+            .map_err(HomescreenError::from)
+            .inspect_err(|err| error!("Cannot insert website tags, {err}"))?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(ServerError::CannotInsertWebsite)?;
+
+        Ok(change)
+    }
+    /// The website other than `website_name` that already uses `alias`, checked up front since a
+    /// failed query would abort the whole transaction on Postgres
+    async fn alias_owner(
+        &self,
+        alias: Option<&str>,
+        website_name: &str,
+        connection: &mut AnyConnection,
+    ) -> Result<Option<String>, sqlx::Error> {
+        let Some(alias) = alias else {
+            return Ok(None);
+        };
+        sqlx::query_scalar(
+            &self
+                .dialect
+                .sql("SELECT website_name FROM websites WHERE alias = ? AND website_name <> ?"),
+        )
+        .bind(alias)
+        .bind(website_name)
+        .fetch_optional(connection)
+        .await
+    }
+    async fn delete_unused_tags(connection: &mut AnyConnection) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM tags WHERE tag_id NOT IN (SELECT tag_id FROM website_tags)")
+            .execute(connection)
+            .await
+            .map(|_| ())
+    }
+}
+
+impl WebsiteStore for SqlStore {
+    async fn get_websites(&self) -> HomescreenResult<Box<[SectionWebsites]>> {
+        let sections = self.get_sections().await?;
+        let mut connection = self
+            .database
+            .acquire()
+            .await
+            .map_err(ServerError::CannotRetrieveWebsites)?;
+
+        let mut websites: Vec<Website> = sqlx::query_as(&self.dialect.sql(&format!(
+            "SELECT {WEBSITE_COLUMNS} FROM websites ORDER BY position, website_name"
+        )))
+        .fetch_all(&mut *connection)
+        .await
+        .map_err(ServerError::CannotRetrieveWebsites)
+        .map_err(HomescreenError::from)
+        .inspect_err(|err| error!("Cannot retrieve websites, {err}"))?;
+        websites.iter_mut().for_each(clear_empty_columns);
+
+        self.load_tags(&mut websites, &mut connection)
+            .await
+            .map_err(ServerError::CannotRetrieveWebsites)
+            .map_err(HomescreenError::from)
+            .inspect_err(|err| error!("Cannot retrieve website tags, {err}"))?;
+
+        Ok(SectionWebsites::group(sections.into_vec(), websites))
+    }
+    async fn get_section_websites(&self, section: &str) -> HomescreenResult<Box<[Website]>> {
+        let mut connection = self
+            .database
+            .acquire()
+            .await
+            .map_err(ServerError::CannotRetrieveWebsites)?;
+
+        let mut websites: Vec<Website> = sqlx::query_as(&self.dialect.sql(&format!(
+            "SELECT {WEBSITE_COLUMNS} FROM websites WHERE section = ? ORDER BY position, website_name"
+        )))
+        .bind(section)
+        .fetch_all(&mut *connection)
+        .await
+        .map_err(ServerError::CannotRetrieveWebsites)
+        .map_err(HomescreenError::from)
+        .inspect_err(|err| error!("Cannot retrieve {section} websites, {err}"))?;
+        websites.iter_mut().for_each(clear_empty_columns);
+
+        self.load_tags(&mut websites, &mut connection)
+            .await
+            .map_err(ServerError::CannotRetrieveWebsites)
+            .map_err(HomescreenError::from)
+            .inspect_err(|err| error!("Cannot retrieve {section} website tags, {err}"))?;
+
+        Ok(websites.into_boxed_slice())
+    }
+    async fn create_or_update_website(&self, website: Website) -> HomescreenResult<WebsiteChange> {
+        self.save_website(website, true).await
+    }
+    async fn create_website(&self, website: Website) -> HomescreenResult {
+        self.save_website(website, false).await.map(|_| ())
+    }
+    async fn update_website(
+        &self,
+        website_name: &str,
+        patch: WebsitePatch,
+    ) -> HomescreenResult<Website> {
+        let mut transaction = self
+            .database
+            .begin()
+            .await
+            .map_err(ServerError::CannotUpdateWebsite)?;
+
+        let query = format!(
+            "SELECT {WEBSITE_COLUMNS} FROM websites WHERE website_name = ?{}",
+            self.dialect.for_update()
+        );
+        let mut existing: Website = sqlx::query_as(&self.dialect.sql(&query))
+            .bind(website_name)
+            .fetch_optional(&mut *transaction)
+            .await
+            .map_err(ServerError::CannotUpdateWebsite)?
+            .ok_or(ServerError::CannotFindWebsite)?;
+        clear_empty_columns(&mut existing);
+
+        let tags_changed = patch.changes_tags();
+        if !tags_changed {
+            existing.tags = self
+                .website_tags(website_name, &mut transaction)
+                .await
+                .map_err(ServerError::CannotUpdateWebsite)?;
+        }
+        let previous_section = existing.section.clone();
+        let website = patch.apply(existing)?;
+
+        if website.website_name != website_name {
+            let name_taken = sqlx::query(
+                &self
+                    .dialect
+                    .sql("SELECT website_name FROM websites WHERE website_name = ?"),
+            )
+            .bind(&website.website_name)
+            .fetch_optional(&mut *transaction)
+            .await
+            .map_err(ServerError::CannotUpdateWebsite)?
+            .is_some()
+                || self
+                    .personal_website_exists(&website.website_name, &mut transaction)
+                    .await
+                    .map_err(ServerError::CannotUpdateWebsite)?;
+
+            if name_taken {
+                return Err(ServerError::WebsiteAlreadyExists.into());
+            }
+        }
+
+        let alias_taken = self
+            .alias_owner(website.alias(), website_name, &mut transaction)
+            .await
+            .map_err(ServerError::CannotUpdateWebsite)?
+            .is_some();
+        if alias_taken {
+            return Err(ServerError::WebsiteAliasAlreadyExists.into());
+        }
+
+        sqlx::query(&self.dialect.sql("UPDATE websites SET website_name = ?, website_link = ?, section = ?, alias = ?, link_template = ? WHERE website_name = ?"))
+        .bind(&website.website_name)
+        .bind(&website.website_link)
+        .bind(&website.section)
+        .bind(&website.alias)
+        .bind(&website.link_template)
+        .bind(website_name)
+        .execute(&mut *transaction)
+        .await
+        .map_err(|err| match Self::map_website_write_error(err) {
+            ServerError::CannotInsertWebsite(err) => ServerError::CannotUpdateWebsite(err),
+            err => err,
+        })
+        .map_err(HomescreenError::from)
+        .inspect_err(|err| error!("Cannot update website, {err}"))?;
+
+        // The tags follow a rename on their own so they only need writing if they were given
+        if tags_changed {
+            self.set_website_tags(&website.website_name, &website.tags, &mut transaction)
+                .await
+                .map_err(ServerError::CannotUpdateWebsite)?;
+        }
+
+        // Moving to another section puts the website at the end of it
+        if website.section != previous_section {
+            let position = self
+                .next_website_position(&website.section, &mut transaction)
+                .await
+                .map_err(ServerError::CannotUpdateWebsite)?;
+
+            sqlx::query(
+                &self
+                    .dialect
+                    .sql("UPDATE websites SET position = ? WHERE website_name = ?"),
+            )
+            .bind(position)
+            .bind(&website.website_name)
+            .execute(&mut *transaction)
+            .await
+            .map_err(ServerError::CannotUpdateWebsite)?;
+        }
+
+        transaction
+            .commit()
+            .await
+            .map_err(ServerError::CannotUpdateWebsite)?;
+
+        Ok(website)
+    }
+    async fn delete_website(&self, website_name: &str) -> HomescreenResult {
+        let mut transaction = self
+            .database
+            .begin()
+            .await
+            .map_err(ServerError::CannotDeleteWebsite)?;
+
+        sqlx::query(
+            &self
+                .dialect
+                .sql("DELETE FROM websites WHERE website_name = ?"),
+        )
+        .bind(website_name)
+        .execute(&mut *transaction)
+        .await
+        .map_err(ServerError::CannotDeleteWebsite)
+        .and_then(|rows| {
+            if rows.rows_affected() == 0 {
+                Err(ServerError::CannotDeleteExistingWebsite)
+            } else {
+                Ok(())
+            }
+        })
+        .map_err(HomescreenError::from)
+        .inspect_err(|err| error!("Cannot delete website, {err}"))?;
+
+        Self::delete_unused_tags(&mut transaction)
+            .await
+            .map_err(ServerError::CannotDeleteWebsite)?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(ServerError::CannotDeleteWebsite)
+            .map_err(HomescreenError::from)
+    }
+    async fn reorder_websites(&self, order: WebsiteOrder) -> HomescreenResult {
+        let mut transaction = self
+            .database
+            .begin()
+            .await
+            .map_err(ServerError::CannotReorderWebsites)?;
+
+        self.order_websites(&order, &mut transaction).await?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(ServerError::CannotReorderWebsites)?;
+
+        Ok(())
+    }
+}
+
+impl SectionStore for SqlStore {
+    async fn get_sections(&self) -> HomescreenResult<Box<[WebsiteSection]>> {
+        sqlx::query_as(&self.dialect.sql("SELECT section_id, display_name, slug, position FROM sections ORDER BY position, section_id"))
+        .fetch_all(&self.database)
+        .await
+        .map_err(ServerError::CannotRetrieveSections)
+        .map_err(HomescreenError::from)
+        .inspect_err(|err| error!("Cannot retrieve sections, {err}"))
+        .map(Vec::into_boxed_slice)
+    }
+    async fn get_section(&self, slug: &str) -> HomescreenResult<WebsiteSection> {
+        sqlx::query_as(
+            &self.dialect.sql(
+                "SELECT section_id, display_name, slug, position FROM sections WHERE slug = ?",
+            ),
+        )
+        .bind(slug)
+        .fetch_optional(&self.database)
+        .await
+        .map_err(ServerError::CannotRetrieveSections)
+        .and_then(|section| section.ok_or(ServerError::CannotFindSection))
+        .map_err(HomescreenError::from)
+        .inspect_err(|err| error!("Cannot retrieve section {slug}, {err}"))
+    }
+    async fn create_section(
+        &self,
+        display_name: String,
+        slug: String,
+        position: Option<i32>,
+    ) -> HomescreenResult<WebsiteSection> {
+        let slug = WebsiteSection::validate_slug(slug)?;
+        let position = match position {
+            Some(position) => position,
+            None => self.next_section_position().await?,
+        };
+
+        sqlx::query(
+            &self
+                .dialect
+                .sql("INSERT INTO sections(display_name, slug, position) VALUES(?, ?, ?)"),
+        )
+        .bind(display_name)
+        .bind(&slug)
+        .bind(position)
+        .execute(&self.database)
+        .await
+        .map_err(|err| match err.as_database_error().map(|err| err.kind()) {
+            Some(ErrorKind::UniqueViolation) => ServerError::SectionAlreadyExists,
+            _ => ServerError::CannotInsertSection(err),
+        })
+        .map_err(HomescreenError::from)
+        .inspect_err(|err| error!("Cannot insert section, {err}"))?;
+
+        self.get_section(&slug).await
+    }
+    async fn update_section(
+        &self,
+        slug: &str,
+        display_name: Option<String>,
+        new_slug: Option<String>,
+        position: Option<i32>,
+    ) -> HomescreenResult<WebsiteSection> {
+        let new_slug = new_slug.map(WebsiteSection::validate_slug).transpose()?;
+
+        let mut transaction = self
+            .database
+            .begin()
+            .await
+            .map_err(ServerError::CannotUpdateSection)?;
+
+        let query = format!(
+            "SELECT section_id, display_name, slug, position FROM sections WHERE slug = ?{}",
+            self.dialect.for_update()
+        );
+        let existing: WebsiteSection = sqlx::query_as(&self.dialect.sql(&query))
+            .bind(slug)
+            .fetch_optional(&mut *transaction)
+            .await
+            .map_err(ServerError::CannotUpdateSection)?
+            .ok_or(ServerError::CannotFindSection)?;
+
+        let section = WebsiteSection {
+            section_id: existing.section_id,
+            display_name: display_name.unwrap_or(existing.display_name),
+            slug: new_slug.unwrap_or(existing.slug),
+            position: position.unwrap_or(existing.position),
+        };
+
+        sqlx::query(&self.dialect.sql(
+            "UPDATE sections SET display_name = ?, slug = ?, position = ? WHERE section_id = ?",
+        ))
+        .bind(&section.display_name)
+        .bind(&section.slug)
+        .bind(section.position)
+        .bind(section.section_id)
+        .execute(&mut *transaction)
+        .await
+        .map_err(|err| match err.as_database_error().map(|err| err.kind()) {
+            Some(ErrorKind::UniqueViolation) => ServerError::SectionAlreadyExists,
+            _ => ServerError::CannotUpdateSection(err),
+        })
+        .map_err(HomescreenError::from)
+        .inspect_err(|err| error!("Cannot update section, {err}"))?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(ServerError::CannotUpdateSection)?;
+
+        Ok(section)
+    }
+    async fn delete_section(&self, slug: &str) -> HomescreenResult {
+        let mut transaction = self
+            .database
+            .begin()
+            .await
+            .map_err(ServerError::CannotDeleteSection)?;
+
+        // The schema refuses it as well, but checking first covers personal websites the same way
+        let in_use = self
+            .section_in_use(slug, &mut transaction)
+            .await
+            .map_err(ServerError::CannotDeleteSection)?;
+        if in_use {
+            return Err(ServerError::SectionIsNotEmpty)
+                .inspect_err(|err| error!("Cannot delete section, {err}"))?;
+        }
+
+        sqlx::query(&self.dialect.sql("DELETE FROM sections WHERE slug = ?"))
+            .bind(slug)
+            .execute(&mut *transaction)
+            .await
+            .map_err(|err| match err.as_database_error().map(|err| err.kind()) {
+                Some(ErrorKind::ForeignKeyViolation) => ServerError::SectionIsNotEmpty,
+                _ => ServerError::CannotDeleteSection(err),
+            })
+            .and_then(|rows| {
+                if rows.rows_affected() == 0 {
+                    Err(ServerError::CannotFindSection)
+                } else {
+                    Ok(())
+                }
+            })
+            .map_err(HomescreenError::from)
+            .inspect_err(|err| error!("Cannot delete section, {err}"))?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(ServerError::CannotDeleteSection)
+            .map_err(HomescreenError::from)
+    }
+}
+
+impl BackupStore for SqlStore {
+    async fn import_backup(&self, backup: Backup, strategy: ImportStrategy) -> HomescreenResult {
+        let backup = backup.validate()?;
+        let overwrite = strategy != ImportStrategy::MergeKeepExisting;
+
+        let mut transaction = self
+            .database
+            .begin()
+            .await
+            .map_err(ServerError::CannotImportBackup)?;
+
+        // Deleting the websites deletes their visits and where people have hidden or moved them
+        // too, so those are put back afterwards for every website that is still there
+        let replace = strategy == ImportStrategy::Replace;
+        let mut visits: Vec<(String, String, i64, i64)> = Vec::new();
+        let mut hidden: Vec<(String, String)> = Vec::new();
+        let mut moved: Vec<(String, String, String, i32)> = Vec::new();
+        if replace {
+            visits =
+                sqlx::query_as("SELECT website_name, day, visits, last_visit FROM website_visits")
+                    .fetch_all(&mut *transaction)
+                    .await
+                    .map_err(ServerError::CannotImportBackup)?;
+            hidden = sqlx::query_as("SELECT owner, website_name FROM hidden_websites")
+                .fetch_all(&mut *transaction)
+                .await
+                .map_err(ServerError::CannotImportBackup)?;
+            moved =
+                sqlx::query_as("SELECT owner, website_name, section, position FROM moved_websites")
+                    .fetch_all(&mut *transaction)
+                    .await
+                    .map_err(ServerError::CannotImportBackup)?;
+
+            for table in ["website_tags", "tags", "websites"] {
+                sqlx::query(&format!("DELETE FROM {table}"))
+                    .execute(&mut *transaction)
+                    .await
+                    .map_err(ServerError::CannotImportBackup)?;
+            }
+
+            // Only the sections missing from the backup are deleted, and personal websites keep
+            // them from being deleted rather than going with them
+            let slugs: Vec<String> = sqlx::query_scalar("SELECT slug FROM sections")
+                .fetch_all(&mut *transaction)
+                .await
+                .map_err(ServerError::CannotImportBackup)?;
+            for slug in slugs.iter().filter(|slug| {
+                !backup
+                    .sections()
+                    .iter()
+                    .any(|section| section.slug() == *slug)
+            }) {
+                let in_use = self
+                    .section_in_use(slug, &mut transaction)
+                    .await
+                    .map_err(ServerError::CannotImportBackup)?;
+                if in_use {
+                    return Err(ServerError::SectionIsNotEmpty)
+                        .inspect_err(|err| error!("Cannot replace section {slug}, {err}"))?;
+                }
+                sqlx::query(&self.dialect.sql("DELETE FROM sections WHERE slug = ?"))
+                    .bind(slug)
+                    .execute(&mut *transaction)
+                    .await
+                    .map_err(ServerError::CannotImportBackup)?;
+            }
+            sqlx::query(&self.dialect.sql("UPDATE sections SET managed = ?"))
+                .bind(false)
+                .execute(&mut *transaction)
+                .await
+                .map_err(ServerError::CannotImportBackup)?;
+        }
+
+        for section in backup.sections() {
+            let exists = sqlx::query(
+                &self
+                    .dialect
+                    .sql("SELECT section_id FROM sections WHERE slug = ?"),
+            )
+            .bind(section.slug())
+            .fetch_optional(&mut *transaction)
+            .await
+            .map_err(ServerError::CannotImportBackup)?
+            .is_some();
+
+            let query = match (exists, overwrite) {
+                (false, _) => "INSERT INTO sections(display_name, position, slug) VALUES(?, ?, ?)",
+                (true, true) => "UPDATE sections SET display_name = ?, position = ? WHERE slug = ?",
+                (true, false) => continue,
+            };
+            sqlx::query(&self.dialect.sql(query))
+                .bind(section.display_name())
+                .bind(section.position())
+                .bind(section.slug())
+                .execute(&mut *transaction)
+                .await
+                .map_err(ServerError::CannotImportBackup)?;
+        }
+
+        for website in backup.websites() {
+            let existing: Option<(String, i32)> = sqlx::query_as(
+                &self
+                    .dialect
+                    .sql("SELECT section, position FROM websites WHERE website_name = ?"),
+            )
+            .bind(website.name())
+            .fetch_optional(&mut *transaction)
+            .await
+            .map_err(ServerError::CannotImportBackup)?;
+            if existing.is_some() && !overwrite {
+                continue;
+            }
+            if existing.is_none() {
+                let personal = self
+                    .personal_website_exists(website.name(), &mut transaction)
+                    .await
+                    .map_err(ServerError::CannotImportBackup)?;
+                match (personal, overwrite) {
+                    (true, true) => return Err(ServerError::WebsiteAlreadyExists.into()),
+                    (true, false) => continue,
+                    _ => {}
+                }
+            }
+
+            // Checked up front since a failed query would abort the whole transaction on Postgres
+            let link_taken = sqlx::query(&self.dialect.sql(
+                "SELECT website_name FROM websites WHERE website_link = ? AND website_name <> ?",
+            ))
+            .bind(website.link())
+            .bind(website.name())
+            .fetch_optional(&mut *transaction)
+            .await
+            .map_err(ServerError::CannotImportBackup)?
+            .is_some();
+            match (link_taken, overwrite) {
+                (true, true) => return Err(ServerError::WebsiteLinkAlreadyExists.into()),
+                (true, false) => continue,
+                _ => {}
+            }
+            let alias_taken = self
+                .alias_owner(website.alias(), website.name(), &mut transaction)
+                .await
+                .map_err(ServerError::CannotImportBackup)?
+                .is_some();
+            match (alias_taken, overwrite) {
+                (true, true) => return Err(ServerError::WebsiteAliasAlreadyExists.into()),
+                (true, false) => continue,
+                _ => {}
+            }
+
+            let query = if existing.is_some() {
+                "UPDATE websites SET website_link = ?, section = ?, position = ?, alias = ?, link_template = ? WHERE website_name = ?"
+            } else {
+                "INSERT INTO websites(website_link, section, position, alias, link_template, website_name) VALUES(?, ?, ?, ?, ?, ?)"
+            };
+            // Websites keep their place unless they move to another section, then they go at the end
+            let position = match existing {
+                Some((section, position)) if section == website.section() => position,
+                _ => self
+                    .next_website_position(website.section(), &mut transaction)
+                    .await
+                    .map_err(ServerError::CannotImportBackup)?,
+            };
+            sqlx::query(&self.dialect.sql(query))
+                .bind(website.link())
+                .bind(website.section())
+                .bind(position)
+                .bind(website.alias())
+                .bind(website.link_template())
+                .bind(website.name())
+                .execute(&mut *transaction)
+                .await
+                .map_err(|err| match Self::map_website_write_error(err) {
+                    ServerError::CannotInsertWebsite(err) => ServerError::CannotImportBackup(err),
+                    err => err,
+                })
+                .map_err(HomescreenError::from)
+                .inspect_err(|err| error!("Cannot import website {}, {err}", website.name()))?;
+
+            self.set_website_tags(website.name(), website.tags(), &mut transaction)
+                .await
+                .map_err(ServerError::CannotImportBackup)?;
+        }
+
+        for (website_name, day, count, last_visit) in visits {
+            if !backup
+                .websites()
+                .iter()
+                .any(|website| website.name() == website_name)
+            {
+                continue;
+            }
+            sqlx::query(&self.dialect.sql(
+                "INSERT INTO website_visits(website_name, day, visits, last_visit) VALUES(?, ?, ?, ?)",
+            ))
+            .bind(website_name)
+            .bind(day)
+            .bind(count)
+            .bind(last_visit)
+            .execute(&mut *transaction)
+            .await
+            .map_err(ServerError::CannotImportBackup)?;
+        }
+        for (owner, website_name) in hidden {
+            if !backup
+                .websites()
+                .iter()
+                .any(|website| website.name() == website_name)
+            {
+                continue;
+            }
+            sqlx::query(
+                &self
+                    .dialect
+                    .sql("INSERT INTO hidden_websites(owner, website_name) VALUES(?, ?)"),
+            )
+            .bind(owner)
+            .bind(website_name)
+            .execute(&mut *transaction)
+            .await
+            .map_err(ServerError::CannotImportBackup)?;
+        }
+        for (owner, website_name, section, position) in moved {
+            let still_there = backup
+                .websites()
+                .iter()
+                .any(|website| website.name() == website_name)
+                && backup
+                    .sections()
+                    .iter()
+                    .any(|existing| existing.slug() == section);
+            if !still_there {
+                continue;
+            }
+            sqlx::query(&self.dialect.sql(
+                "INSERT INTO moved_websites(owner, website_name, section, position) VALUES(?, ?, ?, ?)",
+            ))
+            .bind(owner)
+            .bind(website_name)
+            .bind(section)
+            .bind(position)
+            .execute(&mut *transaction)
+            .await
+            .map_err(ServerError::CannotImportBackup)?;
+        }
+
+        if backup.has_search_engines() {
+            self.import_search_engines(backup.search_engines(), strategy, &mut transaction)
+                .await?;
+        }
+
+        transaction
+            .commit()
+            .await
+            .map_err(ServerError::CannotImportBackup)
+            .map_err(HomescreenError::from)
+            .inspect_err(|err| error!("Cannot import backup, {err}"))
+    }
+    async fn import_bookmarks(
+        &self,
+        bookmarks: Vec<Bookmark>,
+        dry_run: bool,
+    ) -> HomescreenResult<ImportReport> {
+        let mut transaction = self
+            .database
+            .begin()
+            .await
+            .map_err(ServerError::CannotImportBookmarks)?;
+
+        let websites: Vec<(String, String)> =
+            sqlx::query_as("SELECT website_name, website_link FROM websites")
+                .fetch_all(&mut *transaction)
+                .await
+                .map_err(ServerError::CannotImportBookmarks)?;
+        let personal: Vec<String> =
+            sqlx::query_scalar("SELECT website_name FROM personal_websites")
+                .fetch_all(&mut *transaction)
+                .await
+                .map_err(ServerError::CannotImportBookmarks)?;
+        let sections: Vec<String> = sqlx::query_scalar("SELECT slug FROM sections")
+            .fetch_all(&mut *transaction)
+            .await
+            .map_err(ServerError::CannotImportBookmarks)?;
+
+        let (report, sections) = ImportReport::plan(
+            bookmarks,
+            websites,
+            personal.into_iter().collect(),
+            sections.into_iter().collect(),
+            dry_run,
+        );
+        // Dropping the transaction rolls it back, nothing has been written yet anyway
+        if dry_run {
+            return Ok(report);
+        }
+
+        for (display_name, slug) in sections {
+            let position: i64 =
+                sqlx::query_scalar("SELECT COALESCE(MAX(position) + 1, 0) FROM sections")
+                    .fetch_one(&mut *transaction)
+                    .await
+                    .map_err(ServerError::CannotImportBookmarks)?;
+            sqlx::query(
+                &self
+                    .dialect
+                    .sql("INSERT INTO sections(display_name, slug, position) VALUES(?, ?, ?)"),
+            )
+            .bind(display_name)
+            .bind(&slug)
+            .bind(position as i32)
+            .execute(&mut *transaction)
+            .await
+            .map_err(ServerError::CannotImportBookmarks)
+            .map_err(HomescreenError::from)
+            .inspect_err(|err| error!("Cannot import section {slug}, {err}"))?;
+        }
+
+        for website in report.imported_websites() {
+            let position = self
+                .next_website_position(website.section(), &mut transaction)
+                .await
+                .map_err(ServerError::CannotImportBookmarks)?;
+            sqlx::query(&self.dialect.sql(
+                "INSERT INTO websites(website_link, section, position, website_name) VALUES(?, ?, ?, ?)",
+            ))
+            .bind(website.link())
+            .bind(website.section())
+            .bind(position)
+            .bind(website.name())
+            .execute(&mut *transaction)
+            .await
+            .map_err(|err| match Self::map_website_write_error(err) {
+                ServerError::CannotInsertWebsite(err) => ServerError::CannotImportBookmarks(err),
+                err => err,
+            })
+            .map_err(HomescreenError::from)
+            .inspect_err(|err| error!("Cannot import website {}, {err}", website.name()))?;
+        }
+
+        transaction
+            .commit()
+            .await
+            .map_err(ServerError::CannotImportBookmarks)?;
+        Ok(report)
+    }
+}
+
+impl SyncStore for SqlStore {
+    async fn sync_sites(&self, sites: Sites) -> HomescreenResult {
+        let sites = sites.validate()?;
+        let declared_section = |slug: &str| {
+            sites
+                .sections()
+                .iter()
+                .any(|section| section.slug() == slug)
+        };
+        let declared_website = |name: &str| {
+            sites
+                .websites()
+                .iter()
+                .any(|website| website.name() == name)
+        };
+
+        let mut transaction = self
+            .database
+            .begin()
+            .await
+            .map_err(ServerError::CannotSyncSites)?;
+
+        // Websites that are no longer declared go first so their names and links are free again
+        let managed: Vec<String> = sqlx::query_scalar(
+            &self
+                .dialect
+                .sql("SELECT website_name FROM websites WHERE managed = ?"),
+        )
+        .bind(true)
+        .fetch_all(&mut *transaction)
+        .await
+        .map_err(ServerError::CannotSyncSites)?;
+        for website_name in managed.iter().filter(|name| !declared_website(name)) {
+            sqlx::query(
+                &self
+                    .dialect
+                    .sql("DELETE FROM websites WHERE website_name = ?"),
+            )
+            .bind(website_name)
+            .execute(&mut *transaction)
+            .await
+            .map_err(ServerError::CannotSyncSites)?;
+            info!("Deleted website {website_name}, it is no longer declared");
+        }
+
+        for (position, section) in sites.sections().iter().enumerate() {
+            let exists = sqlx::query(
+                &self
+                    .dialect
+                    .sql("SELECT section_id FROM sections WHERE slug = ?"),
+            )
+            .bind(section.slug())
+            .fetch_optional(&mut *transaction)
+            .await
+            .map_err(ServerError::CannotSyncSites)?
+            .is_some();
+
+            let query = if exists {
+                "UPDATE sections SET display_name = ?, position = ?, managed = ? WHERE slug = ?"
+            } else {
+                "INSERT INTO sections(display_name, position, managed, slug) VALUES(?, ?, ?, ?)"
+            };
+            sqlx::query(&self.dialect.sql(query))
+                .bind(section.display_name())
+                .bind(position as i32)
+                .bind(true)
+                .bind(section.slug())
+                .execute(&mut *transaction)
+                .await
+                .map_err(ServerError::CannotSyncSites)?;
+            if !exists {
+                info!("Created section {}", section.slug());
+            }
+        }
+
+        let mut order = WebsiteOrder::new();
+        for website in sites.websites() {
+            // Checked up front since a failed query would abort the whole transaction on Postgres
+            let owner: Option<String> = sqlx::query_scalar(&self.dialect.sql(
+                "SELECT website_name FROM websites WHERE website_link = ? AND website_name <> ?",
+            ))
+            .bind(website.link())
+            .bind(website.name())
+            .fetch_optional(&mut *transaction)
+            .await
+            .map_err(ServerError::CannotSyncSites)?;
+            if let Some(owner) = owner {
+                return Err(ConfigError::SiteLinkAlreadyUsed(
+                    website.name().to_owned(),
+                    owner,
+                ))
+                .inspect_err(|err| error!("Cannot sync sites, {err}"))?;
+            }
+            let owner = self
+                .alias_owner(website.alias(), website.name(), &mut transaction)
+                .await
+                .map_err(ServerError::CannotSyncSites)?;
+            if let Some(owner) = owner {
+                return Err(ConfigError::SiteAliasAlreadyUsed(
+                    website.name().to_owned(),
+                    owner,
+                ))
+                .inspect_err(|err| error!("Cannot sync sites, {err}"))?;
+            }
+
+            let exists = sqlx::query(
+                &self
+                    .dialect
+                    .sql("SELECT website_name FROM websites WHERE website_name = ?"),
+            )
+            .bind(website.name())
+            .fetch_optional(&mut *transaction)
+            .await
+            .map_err(ServerError::CannotSyncSites)?
+            .is_some();
+            if !exists {
+                let personal = self
+                    .personal_website_exists(website.name(), &mut transaction)
+                    .await
+                    .map_err(ServerError::CannotSyncSites)?;
+                if personal {
+                    return Err(ConfigError::SiteNameUsedByPersonalWebsite(
+                        website.name().to_owned(),
+                    ))
+                    .inspect_err(|err| error!("Cannot sync sites, {err}"))?;
+                }
+            }
+
+            let query = if exists {
+                "UPDATE websites SET website_link = ?, section = ?, alias = ?, link_template = ?, managed = ? WHERE website_name = ?"
+            } else {
+                "INSERT INTO websites(website_link, section, alias, link_template, managed, website_name) VALUES(?, ?, ?, ?, ?, ?)"
+            };
+            sqlx::query(&self.dialect.sql(query))
+                .bind(website.link())
+                .bind(website.section())
+                .bind(website.alias())
+                .bind(website.link_template())
+                .bind(true)
+                .bind(website.name())
+                .execute(&mut *transaction)
+                .await
+                .map_err(|err| match Self::map_website_write_error(err) {
+                    ServerError::CannotInsertWebsite(err) => ServerError::CannotSyncSites(err),
+                    err => err,
+                })
+                .map_err(HomescreenError::from)
+                .inspect_err(|err| error!("Cannot sync website {}, {err}", website.name()))?;
+            if !exists {
+                info!("Created website {}", website.name());
+            }
+
+            self.set_website_tags(website.name(), website.tags(), &mut transaction)
+                .await
+                .map_err(ServerError::CannotSyncSites)?;
+
+            order
+                .entry(website.section().to_owned())
+                .or_default()
+                .push(website.name().to_owned());
+        }
+        // Declared websites come first in their section in the order they are declared
+        self.order_websites(&order, &mut transaction).await?;
+
+        let managed: Vec<String> = sqlx::query_scalar(
+            &self
+                .dialect
+                .sql("SELECT slug FROM sections WHERE managed = ?"),
+        )
+        .bind(true)
+        .fetch_all(&mut *transaction)
+        .await
+        .map_err(ServerError::CannotSyncSites)?;
+        for slug in managed.iter().filter(|slug| !declared_section(slug)) {
+            let not_empty = self
+                .section_in_use(slug, &mut transaction)
+                .await
+                .map_err(ServerError::CannotSyncSites)?;
+            if not_empty {
+                warn!(
+                    "Section {slug} is no longer declared but is kept since it still has websites"
+                );
+                continue;
+            }
+
+            sqlx::query(&self.dialect.sql("DELETE FROM sections WHERE slug = ?"))
+                .bind(slug)
+                .execute(&mut *transaction)
+                .await
+                .map_err(ServerError::CannotSyncSites)?;
+            info!("Deleted section {slug}, it is no longer declared");
+        }
+
+        // Sections that aren't declared keep their order after the declared ones
+        let undeclared: Vec<String> = sqlx::query_scalar(
+            &self
+                .dialect
+                .sql("SELECT slug FROM sections ORDER BY position, section_id"),
+        )
+        .fetch_all(&mut *transaction)
+        .await
+        .map_err(ServerError::CannotSyncSites)?;
+        for (position, slug) in undeclared
+            .iter()
+            .filter(|slug| !declared_section(slug))
+            .enumerate()
+        {
+            sqlx::query(
+                &self
+                    .dialect
+                    .sql("UPDATE sections SET position = ? WHERE slug = ?"),
+            )
+            .bind((sites.sections().len() + position) as i32)
+            .bind(slug)
+            .execute(&mut *transaction)
+            .await
+            .map_err(ServerError::CannotSyncSites)?;
+        }
+
+        Self::delete_unused_tags(&mut transaction)
+            .await
+            .map_err(ServerError::CannotSyncSites)?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(ServerError::CannotSyncSites)
+            .map_err(HomescreenError::from)
+            .inspect_err(|err| error!("Cannot sync sites, {err}"))
+    }
+}
+
+impl VisitStore for SqlStore {
+    async fn record_visit(
+        &self,
+        website_name: &str,
+        visited_at: DateTime<Utc>,
+    ) -> HomescreenResult<Website> {
+        let mut transaction = self
+            .database
+            .begin()
+            .await
+            .map_err(ServerError::CannotRecordVisit)?;
+
+        // Names are looked up first so an alias can never hide another website's name
+        let mut website = None;
+        for column in ["website_name", "alias"] {
+            website = sqlx::query_as(&self.dialect.sql(&format!(
+                "SELECT {WEBSITE_COLUMNS} FROM websites WHERE {column} = ?"
+            )))
+            .bind(website_name)
+            .fetch_optional(&mut *transaction)
+            .await
+            .map_err(ServerError::CannotRecordVisit)?;
+            if website.is_some() {
+                break;
+            }
+        }
+        let mut website: Website = website.ok_or(ServerError::CannotFindWebsite)?;
+        clear_empty_columns(&mut website);
+        website.tags = self
+            .website_tags(&website.website_name, &mut transaction)
+            .await
+            .map_err(ServerError::CannotRecordVisit)?;
+
+        sqlx::query(&self.dialect.sql(self.dialect.record_visit()))
+            .bind(&website.website_name)
+            .bind(visited_at.date_naive().to_string())
+            .bind(visited_at.timestamp())
+            .execute(&mut *transaction)
+            .await
+            .map_err(ServerError::CannotRecordVisit)
+            .map_err(HomescreenError::from)
+            .inspect_err(|err| error!("Cannot record visit to {website_name}, {err}"))?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(ServerError::CannotRecordVisit)?;
+
+        Ok(website)
+    }
+    async fn website_stats(&self, website_name: &str) -> HomescreenResult<WebsiteStats> {
+        let mut connection = self
+            .database
+            .acquire()
+            .await
+            .map_err(ServerError::CannotRetrieveVisits)?;
+
+        sqlx::query(
+            &self
+                .dialect
+                .sql("SELECT website_name FROM websites WHERE website_name = ?"),
+        )
+        .bind(website_name)
+        .fetch_optional(&mut *connection)
+        .await
+        .map_err(ServerError::CannotRetrieveVisits)?
+        .ok_or(ServerError::CannotFindWebsite)?;
+
+        let days: Vec<(String, i64, i64)> = sqlx::query_as(&self.dialect.sql(
+            "SELECT day, visits, last_visit FROM website_visits WHERE website_name = ? ORDER BY day",
+        ))
+        .bind(website_name)
+        .fetch_all(&mut *connection)
+        .await
+        .map_err(ServerError::CannotRetrieveVisits)
+        .map_err(HomescreenError::from)
+        .inspect_err(|err| error!("Cannot retrieve visits to {website_name}, {err}"))?;
+
+        let last_visited = days
+            .iter()
+            .map(|(_, _, last_visit)| *last_visit)
+            .max()
+            .and_then(|last_visit| DateTime::from_timestamp(last_visit, 0));
+        let days = days
+            .into_iter()
+            .filter_map(|(day, visits, _)| {
+                Some(DailyVisits::new(
+                    day.parse::<NaiveDate>().ok()?,
+                    visits as u64,
+                ))
+            })
+            .collect();
+
+        Ok(WebsiteStats::new(
+            website_name.to_owned(),
+            last_visited,
+            days,
+        ))
+    }
+    async fn visit_counts(&self) -> HomescreenResult<HashMap<String, u64>> {
+        // Summed here rather than in the query since the type of SUM differs between databases
+        let days: Vec<(String, i64)> =
+            sqlx::query_as("SELECT website_name, visits FROM website_visits")
+                .fetch_all(&self.database)
+                .await
+                .map_err(ServerError::CannotRetrieveVisits)
+                .map_err(HomescreenError::from)
+                .inspect_err(|err| error!("Cannot retrieve visits, {err}"))?;
+
+        let mut counts = HashMap::new();
+        for (website_name, visits) in days {
+            *counts.entry(website_name).or_default() += visits as u64;
+        }
+        Ok(counts)
+    }
+}
+
+impl SearchEngineStore for SqlStore {
+    async fn get_search_engines(&self) -> HomescreenResult<Box<[SearchEngine]>> {
+        // The Any driver can't decode booleans from SQLite so the flag is read as a number
+        let engines: Vec<(String, String, String, i64)> = sqlx::query_as(&self.dialect.sql(
+            "SELECT name, url_template, prefix, CASE WHEN is_default THEN 1 ELSE 0 END FROM search_engines ORDER BY search_engine_id",
+        ))
+        .fetch_all(&self.database)
+        .await
+        .map_err(ServerError::CannotRetrieveSearchEngines)
+        .map_err(HomescreenError::from)
+        .inspect_err(|err| error!("Cannot retrieve search engines, {err}"))?;
+
+        Ok(engines
+            .into_iter()
+            .map(|(name, url_template, prefix, is_default)| SearchEngine {
+                name,
+                url_template,
+                prefix,
+                is_default: is_default != 0,
+            })
+            .collect())
+    }
+    async fn create_or_update_search_engine(&self, engine: SearchEngine) -> HomescreenResult {
+        let mut transaction = self
+            .database
+            .begin()
+            .await
+            .map_err(ServerError::CannotSaveSearchEngine)?;
+
+        // Checked up front since a failed query would abort the whole transaction on Postgres
+        let prefix_taken = sqlx::query(
+            &self
+                .dialect
+                .sql("SELECT name FROM search_engines WHERE prefix = ? AND name <> ?"),
+        )
+        .bind(&engine.prefix)
+        .bind(&engine.name)
+        .fetch_optional(&mut *transaction)
+        .await
+        .map_err(ServerError::CannotSaveSearchEngine)?
+        .is_some();
+        if prefix_taken {
+            return Err(ServerError::SearchPrefixAlreadyExists.into());
+        }
+
+        if engine.is_default {
+            sqlx::query(&self.dialect.sql("UPDATE search_engines SET is_default = ?"))
+                .bind(false)
+                .execute(&mut *transaction)
+                .await
+                .map_err(ServerError::CannotSaveSearchEngine)?;
+        }
+
+        let exists = sqlx::query(
+            &self
+                .dialect
+                .sql("SELECT name FROM search_engines WHERE name = ?"),
+        )
+        .bind(&engine.name)
+        .fetch_optional(&mut *transaction)
+        .await
+        .map_err(ServerError::CannotSaveSearchEngine)?
+        .is_some();
+        let query = if exists {
+            "UPDATE search_engines SET url_template = ?, prefix = ?, is_default = ? WHERE name = ?"
+        } else {
+            "INSERT INTO search_engines(url_template, prefix, is_default, name) VALUES(?, ?, ?, ?)"
+        };
+        sqlx::query(&self.dialect.sql(query))
+            .bind(&engine.url_template)
+            .bind(&engine.prefix)
+            .bind(engine.is_default)
+            .bind(&engine.name)
+            .execute(&mut *transaction)
+            .await
+            .map_err(ServerError::CannotSaveSearchEngine)
+            .map_err(HomescreenError::from)
+            .inspect_err(|err| error!("Cannot save search engine {}, {err}", engine.name))?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(ServerError::CannotSaveSearchEngine)
+            .map_err(HomescreenError::from)
+    }
+    async fn delete_search_engine(&self, name: &str) -> HomescreenResult {
+        let deleted = sqlx::query(
+            &self
+                .dialect
+                .sql("DELETE FROM search_engines WHERE name = ?"),
+        )
+        .bind(name)
+        .execute(&self.database)
+        .await
+        .map_err(ServerError::CannotDeleteSearchEngine)
+        .map_err(HomescreenError::from)
+        .inspect_err(|err| error!("Cannot delete search engine {name}, {err}"))?;
+
+        if deleted.rows_affected() == 0 {
+            return Err(ServerError::CannotFindSearchEngine.into());
+        }
+        Ok(())
+    }
+}
+
+impl SqlStore {
+    /// Tokens are stored with their scope as text and their creation time as unix seconds since
+    /// the Any driver can't decode either
+    fn token_from_row(
+        (name, scope, created_at): (String, String, i64),
+    ) -> Result<ApiToken, sqlx::Error> {
+        Ok(ApiToken {
+            name,
+            scope: scope
+                .parse()
+                .map_err(|err: HomescreenError| sqlx::Error::Decode(err.to_string().into()))?,
+            created_at: DateTime::from_timestamp(created_at, 0).unwrap_or_default(),
+        })
+    }
+}
+
+impl TokenStore for SqlStore {
+    async fn get_tokens(&self) -> HomescreenResult<Box<[ApiToken]>> {
+        let rows: Vec<(String, String, i64)> = sqlx::query_as(
+            &self
+                .dialect
+                .sql("SELECT name, scope, created_at FROM api_tokens ORDER BY created_at, name"),
+        )
+        .fetch_all(&self.database)
+        .await
+        .map_err(ServerError::CannotRetrieveTokens)?;
+
+        rows.into_iter()
+            .map(Self::token_from_row)
+            .collect::<Result<_, _>>()
+            .map_err(ServerError::CannotRetrieveTokens)
+            .map_err(HomescreenError::from)
+            .inspect_err(|err| error!("Cannot retrieve api tokens, {err}"))
+    }
+    async fn create_token(&self, token: ApiToken, token_hash: String) -> HomescreenResult {
+        let mut transaction = self
+            .database
+            .begin()
+            .await
+            .map_err(ServerError::CannotInsertToken)?;
+
+        // Checked up front since a failed query would abort the whole transaction on Postgres
+        let exists = sqlx::query(
+            &self
+                .dialect
+                .sql("SELECT name FROM api_tokens WHERE name = ?"),
+        )
+        .bind(&token.name)
+        .fetch_optional(&mut *transaction)
+        .await
+        .map_err(ServerError::CannotInsertToken)?
+        .is_some();
+        if exists {
+            return Err(ServerError::TokenAlreadyExists.into());
+        }
+
+        sqlx::query(
+            &self.dialect.sql(
+                "INSERT INTO api_tokens(name, token_hash, scope, created_at) VALUES(?, ?, ?, ?)",
+            ),
+        )
+        .bind(&token.name)
+        .bind(token_hash)
+        .bind(token.scope.to_string())
+        .bind(token.created_at.timestamp())
+        .execute(&mut *transaction)
+        .await
+        .map_err(ServerError::CannotInsertToken)
+        .map_err(HomescreenError::from)
+        .inspect_err(|err| error!("Cannot insert api token {}, {err}", token.name))?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(ServerError::CannotInsertToken)
+            .map_err(HomescreenError::from)
+    }
+    async fn find_token(&self, token_hash: &str) -> HomescreenResult<Option<ApiToken>> {
+        let row: Option<(String, String, i64)> = sqlx::query_as(
+            &self
+                .dialect
+                .sql("SELECT name, scope, created_at FROM api_tokens WHERE token_hash = ?"),
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.database)
+        .await
+        .map_err(ServerError::CannotRetrieveTokens)?;
+
+        row.map(Self::token_from_row)
+            .transpose()
+            .map_err(ServerError::CannotRetrieveTokens)
+            .map_err(HomescreenError::from)
+            .inspect_err(|err| error!("Cannot retrieve api token, {err}"))
+    }
+    async fn delete_token(&self, name: &str) -> HomescreenResult {
+        let deleted = sqlx::query(&self.dialect.sql("DELETE FROM api_tokens WHERE name = ?"))
+            .bind(name)
+            .execute(&self.database)
+            .await
+            .map_err(ServerError::CannotDeleteToken)
+            .map_err(HomescreenError::from)
+            .inspect_err(|err| error!("Cannot delete api token {name}, {err}"))?;
+
+        if deleted.rows_affected() == 0 {
+            return Err(ServerError::CannotFindToken.into());
+        }
+        Ok(())
+    }
+}
+
+impl SqlStore {
+    /// Users are stored with their role as text and their creation time as unix seconds, the
+    /// same as api tokens
+    fn user_from_row((name, role, created_at): (String, String, i64)) -> Result<User, sqlx::Error> {
+        Ok(User {
+            name,
+            role: role
+                .parse()
+                .map_err(|err: HomescreenError| sqlx::Error::Decode(err.to_string().into()))?,
+            created_at: DateTime::from_timestamp(created_at, 0).unwrap_or_default(),
+        })
+    }
+}
+
+impl UserStore for SqlStore {
+    async fn get_users(&self) -> HomescreenResult<Box<[User]>> {
+        let rows: Vec<(String, String, i64)> = sqlx::query_as(
+            &self
+                .dialect
+                .sql("SELECT name, role, created_at FROM users ORDER BY created_at, name"),
+        )
+        .fetch_all(&self.database)
+        .await
+        .map_err(ServerError::CannotRetrieveUsers)?;
+
+        rows.into_iter()
+            .map(Self::user_from_row)
+            .collect::<Result<_, _>>()
+            .map_err(ServerError::CannotRetrieveUsers)
+            .map_err(HomescreenError::from)
+            .inspect_err(|err| error!("Cannot retrieve users, {err}"))
+    }
+    async fn create_or_update_user(
+        &self,
+        user: User,
+        password_hash: String,
+    ) -> HomescreenResult<bool> {
+        let mut transaction = self
+            .database
+            .begin()
+            .await
+            .map_err(ServerError::CannotSaveUser)?;
+
+        let exists = sqlx::query(&self.dialect.sql("SELECT name FROM users WHERE name = ?"))
+            .bind(&user.name)
+            .fetch_optional(&mut *transaction)
+            .await
+            .map_err(ServerError::CannotSaveUser)?
+            .is_some();
+        let query = if exists {
+            "UPDATE users SET password_hash = ?, role = ? WHERE name = ?"
+        } else {
+            "INSERT INTO users(password_hash, role, name, created_at) VALUES(?, ?, ?, ?)"
+        };
+        let query = self.dialect.sql(query);
+        let mut query = sqlx::query(&query)
+            .bind(password_hash)
+            .bind(user.role.to_string())
+            .bind(&user.name);
+        if !exists {
+            query = query.bind(user.created_at.timestamp());
+        }
+        query
+            .execute(&mut *transaction)
+            .await
+            .map_err(ServerError::CannotSaveUser)
+            .map_err(HomescreenError::from)
+            .inspect_err(|err| error!("Cannot save user {}, {err}", user.name))?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(ServerError::CannotSaveUser)?;
+        Ok(!exists)
+    }
+    async fn create_or_update_external_user(
+        &self,
+        user: User,
+        issuer: &str,
+        subject: &str,
+    ) -> HomescreenResult<User> {
+        let mut transaction = self
+            .database
+            .begin()
+            .await
+            .map_err(ServerError::CannotSaveUser)?;
+
+        let linked: Option<(String, String, i64)> = sqlx::query_as(&self.dialect.sql(
+            "SELECT name, role, created_at FROM users WHERE oidc_issuer = ? AND oidc_subject = ?",
+        ))
+        .bind(issuer)
+        .bind(subject)
+        .fetch_optional(&mut *transaction)
+        .await
+        .map_err(ServerError::CannotSaveUser)?;
+        let saved = match linked {
+            Some(row) => {
+                let linked = Self::user_from_row(row).map_err(ServerError::CannotSaveUser)?;
+                sqlx::query(&self.dialect.sql("UPDATE users SET role = ? WHERE name = ?"))
+                    .bind(user.role.to_string())
+                    .bind(&linked.name)
+                    .execute(&mut *transaction)
+                    .await
+                    .map_err(ServerError::CannotSaveUser)?;
+                User {
+                    role: user.role,
+                    ..linked
+                }
+            }
+            None => {
+                let existing: Option<(String, String)> = sqlx::query_as(&self.dialect.sql(
+                    "SELECT password_hash, COALESCE(oidc_issuer, '') FROM users WHERE name = ?",
+                ))
+                .bind(&user.name)
+                .fetch_optional(&mut *transaction)
+                .await
+                .map_err(ServerError::CannotSaveUser)?;
+                let query = match &existing {
+                    // Users from an identity provider were kept by name alone before, an empty hash
+                    // never matches a password so nobody else can have made them
+                    Some((password_hash, linked_issuer))
+                        if password_hash.is_empty() && linked_issuer.is_empty() =>
+                    {
+                        "UPDATE users SET role = ?, oidc_issuer = ?, oidc_subject = ? WHERE name = ?"
+                    }
+                    Some(_) => {
+                        warn!(
+                            "Refused to link {} to an account at {issuer}, the name is taken",
+                            user.name
+                        );
+                        return Err(ServerError::UserAlreadyExists(user.name).into());
+                    }
+                    None => {
+                        "INSERT INTO users(role, oidc_issuer, oidc_subject, name, password_hash, created_at) VALUES(?, ?, ?, ?, '', ?)"
+                    }
+                };
+                let query = self.dialect.sql(query);
+                let mut query = sqlx::query(&query)
+                    .bind(user.role.to_string())
+                    .bind(issuer)
+                    .bind(subject)
+                    .bind(&user.name);
+                if existing.is_none() {
+                    query = query.bind(user.created_at.timestamp());
+                }
+                query
+                    .execute(&mut *transaction)
+                    .await
+                    .map_err(ServerError::CannotSaveUser)
+                    .map_err(HomescreenError::from)
+                    .inspect_err(|err| error!("Cannot save user {}, {err}", user.name))?;
+                user
+            }
+        };
+
+        transaction
+            .commit()
+            .await
+            .map_err(ServerError::CannotSaveUser)?;
+        Ok(saved)
+    }
+    async fn find_user(&self, name: &str) -> HomescreenResult<Option<(User, String)>> {
+        let row: Option<(String, String, i64, String)> = sqlx::query_as(
+            &self
+                .dialect
+                .sql("SELECT name, role, created_at, password_hash FROM users WHERE name = ?"),
+        )
+        .bind(name)
+        .fetch_optional(&self.database)
+        .await
+        .map_err(ServerError::CannotRetrieveUsers)?;
+
+        row.map(|(name, role, created_at, password_hash)| {
+            Ok((
+                Self::user_from_row((name, role, created_at))?,
+                password_hash,
+            ))
+        })
+        .transpose()
+        .map_err(ServerError::CannotRetrieveUsers)
+        .map_err(HomescreenError::from)
+        .inspect_err(|err| error!("Cannot retrieve user {name}, {err}"))
+    }
+    async fn delete_user(&self, name: &str) -> HomescreenResult {
+        let deleted = sqlx::query(&self.dialect.sql("DELETE FROM users WHERE name = ?"))
+            .bind(name)
+            .execute(&self.database)
+            .await
+            .map_err(ServerError::CannotDeleteUser)
+            .map_err(HomescreenError::from)
+            .inspect_err(|err| error!("Cannot delete user {name}, {err}"))?;
+
+        if deleted.rows_affected() == 0 {
+            return Err(ServerError::CannotFindUser.into());
+        }
+        Ok(())
+    }
+    async fn create_session(
+        &self,
+        session_hash: String,
+        name: &str,
+        expires_at: DateTime<Utc>,
+    ) -> HomescreenResult {
+        sqlx::query(
+            &self
+                .dialect
+                .sql("INSERT INTO sessions(session_hash, name, expires_at) VALUES(?, ?, ?)"),
+        )
+        .bind(session_hash)
+        .bind(name)
+        .bind(expires_at.timestamp())
+        .execute(&self.database)
+        .await
+        .map_err(ServerError::CannotCreateSession)
+        .map_err(HomescreenError::from)
+        .inspect_err(|err| error!("Cannot create session for {name}, {err}"))?;
+
+        Ok(())
+    }
+    async fn find_session(
+        &self,
+        session_hash: &str,
+        now: DateTime<Utc>,
+    ) -> HomescreenResult<Option<User>> {
+        let row: Option<(String, String, i64)> = sqlx::query_as(&self.dialect.sql(
+            "SELECT users.name, users.role, users.created_at FROM sessions \
+             JOIN users ON users.name = sessions.name \
+             WHERE sessions.session_hash = ? AND sessions.expires_at > ?",
+        ))
+        .bind(session_hash)
+        .bind(now.timestamp())
+        .fetch_optional(&self.database)
+        .await
+        .map_err(ServerError::CannotRetrieveSession)?;
+
+        row.map(Self::user_from_row)
+            .transpose()
+            .map_err(ServerError::CannotRetrieveSession)
+            .map_err(HomescreenError::from)
+            .inspect_err(|err| error!("Cannot retrieve session, {err}"))
+    }
+    async fn delete_session(&self, session_hash: &str) -> HomescreenResult {
+        sqlx::query(
+            &self
+                .dialect
+                .sql("DELETE FROM sessions WHERE session_hash = ?"),
+        )
+        .bind(session_hash)
+        .execute(&self.database)
+        .await
+        .map_err(ServerError::CannotDeleteSession)
+        .map_err(HomescreenError::from)
+        .inspect_err(|err| error!("Cannot delete session, {err}"))?;
+
+        Ok(())
+    }
+}
+
+impl PersonalStore for SqlStore {
+    async fn get_overlay(&self, owner: &str) -> HomescreenResult<Overlay> {
+        let mut connection = self
+            .database
+            .acquire()
+            .await
+            .map_err(ServerError::CannotRetrievePersonalHomescreen)?;
+
+        self.overlay(owner, &mut connection)
+            .await
+            .map_err(ServerError::CannotRetrievePersonalHomescreen)
+            .map_err(HomescreenError::from)
+            .inspect_err(|err| error!("Cannot retrieve personal homescreen of {owner}, {err}"))
+    }
+    async fn create_or_update_personal_website(
+        &self,
+        owner: &str,
+        website: Website,
+    ) -> HomescreenResult<WebsiteChange> {
+        let mut transaction = self
+            .database
+            .begin()
+            .await
+            .map_err(ServerError::CannotUpdatePersonalHomescreen)?;
+
+        let overlay = self
+            .overlay(owner, &mut transaction)
+            .await
+            .map_err(ServerError::CannotUpdatePersonalHomescreen)?;
+        let shared = sqlx::query(
+            &self
+                .dialect
+                .sql("SELECT website_name FROM websites WHERE website_name = ?"),
+        )
+        .bind(&website.website_name)
+        .fetch_optional(&mut *transaction)
+        .await
+        .map_err(ServerError::CannotUpdatePersonalHomescreen)?
+        .is_some();
+        if shared {
+            return Err(ServerError::WebsiteAlreadyExists.into());
+        }
+        let owner_exists = sqlx::query(&self.dialect.sql("SELECT name FROM users WHERE name = ?"))
+            .bind(owner)
+            .fetch_optional(&mut *transaction)
+            .await
+            .map_err(ServerError::CannotUpdatePersonalHomescreen)?
+            .is_some();
+        if !owner_exists {
+            return Err(ServerError::CannotFindUser.into());
+        }
+
+        // Personal websites keep their place unless they move to another section, the same as
+        // shared ones
+        let (query, change, position) = match overlay.website(&website.website_name) {
+            Some(existing) if existing.section == website.section => (
+                "UPDATE personal_websites SET website_link = ?, section = ? WHERE owner = ? AND website_name = ?",
+                WebsiteChange::Updated,
+                None,
+            ),
+            Some(_) => (
+                "UPDATE personal_websites SET website_link = ?, section = ?, position = ? WHERE owner = ? AND website_name = ?",
+                WebsiteChange::Updated,
+                Some(overlay.next_position(&website.section)),
+            ),
+            None => (
+                "INSERT INTO personal_websites(website_link, section, position, owner, website_name) VALUES(?, ?, ?, ?, ?)",
+                WebsiteChange::Created,
+                Some(overlay.next_position(&website.section)),
+            ),
+        };
+
+        let query = self.dialect.sql(query);
+        let mut query = sqlx::query(&query)
+            .bind(&website.website_link)
+            .bind(&website.section);
+        if let Some(position) = position {
+            query = query.bind(position);
+        }
+        query
+            .bind(owner)
+            .bind(&website.website_name)
+            .execute(&mut *transaction)
+            .await
+            .map_err(|err| match err.as_database_error().map(|err| err.kind()) {
+                Some(ErrorKind::ForeignKeyViolation) => ServerError::CannotFindSection,
+                _ => ServerError::CannotUpdatePersonalHomescreen(err),
+            })
+            .map_err(HomescreenError::from)
+            .inspect_err(|err| error!("Cannot save personal website of {owner}, {err}"))?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(ServerError::CannotUpdatePersonalHomescreen)?;
+
+        Ok(change)
+    }
+    async fn delete_personal_website(&self, owner: &str, website_name: &str) -> HomescreenResult {
+        let deleted = sqlx::query(
+            &self
+                .dialect
+                .sql("DELETE FROM personal_websites WHERE owner = ? AND website_name = ?"),
+        )
+        .bind(owner)
+        .bind(website_name)
+        .execute(&self.database)
+        .await
+        .map_err(ServerError::CannotUpdatePersonalHomescreen)
+        .map_err(HomescreenError::from)
+        .inspect_err(|err| error!("Cannot delete personal website of {owner}, {err}"))?;
+
+        if deleted.rows_affected() == 0 {
+            return Err(ServerError::CannotFindWebsite.into());
+        }
+        Ok(())
+    }
+    async fn set_website_hidden(
+        &self,
+        owner: &str,
+        website_name: &str,
+        hidden: bool,
+    ) -> HomescreenResult {
+        let mut transaction = self
+            .database
+            .begin()
+            .await
+            .map_err(ServerError::CannotUpdatePersonalHomescreen)?;
+
+        let exists = sqlx::query(
+            &self
+                .dialect
+                .sql("SELECT website_name FROM websites WHERE website_name = ?"),
+        )
+        .bind(website_name)
+        .fetch_optional(&mut *transaction)
+        .await
+        .map_err(ServerError::CannotUpdatePersonalHomescreen)?
+        .is_some();
+        if !exists {
+            return Err(ServerError::CannotFindWebsite.into());
+        }
+
+        // Clearing it first means hiding a website twice is the same as hiding it once
+        sqlx::query(
+            &self
+                .dialect
+                .sql("DELETE FROM hidden_websites WHERE owner = ? AND website_name = ?"),
+        )
+        .bind(owner)
+        .bind(website_name)
+        .execute(&mut *transaction)
+        .await
+        .map_err(ServerError::CannotUpdatePersonalHomescreen)?;
+        if hidden {
+            sqlx::query(
+                &self
+                    .dialect
+                    .sql("INSERT INTO hidden_websites(owner, website_name) VALUES(?, ?)"),
+            )
+            .bind(owner)
+            .bind(website_name)
+            .execute(&mut *transaction)
+            .await
+            .map_err(|err| match err.as_database_error().map(|err| err.kind()) {
+                Some(ErrorKind::ForeignKeyViolation) => ServerError::CannotFindUser,
+                _ => ServerError::CannotUpdatePersonalHomescreen(err),
+            })
+            .map_err(HomescreenError::from)
+            .inspect_err(|err| error!("Cannot hide website from {owner}, {err}"))?;
+        }
+
+        transaction
+            .commit()
+            .await
+            .map_err(ServerError::CannotUpdatePersonalHomescreen)?;
+
+        Ok(())
+    }
+    async fn reorder_personal_websites(
+        &self,
+        owner: &str,
+        order: WebsiteOrder,
+    ) -> HomescreenResult {
+        let mut transaction = self
+            .database
+            .begin()
+            .await
+            .map_err(ServerError::CannotUpdatePersonalHomescreen)?;
+
+        let shared = sqlx::query_scalar(&self.dialect.sql("SELECT website_name FROM websites"))
+            .fetch_all(&mut *transaction)
+            .await
+            .map_err(ServerError::CannotUpdatePersonalHomescreen)?
+            .into_iter()
+            .collect();
+        let mut overlay = self
+            .overlay(owner, &mut transaction)
+            .await
+            .map_err(ServerError::CannotUpdatePersonalHomescreen)?;
+        overlay
+            .reorder(&order, &shared)
+            .inspect_err(|err| error!("Cannot reorder personal websites of {owner}, {err}"))?;
+
+        let reorder_error = |err: sqlx::Error| match err.as_database_error().map(|err| err.kind()) {
+            Some(ErrorKind::ForeignKeyViolation) => ServerError::CannotFindSection,
+            _ => ServerError::CannotUpdatePersonalHomescreen(err),
+        };
+        for (website, position) in &overlay.websites {
+            sqlx::query(&self.dialect.sql(
+                "UPDATE personal_websites SET section = ?, position = ? WHERE owner = ? AND website_name = ?",
+            ))
+            .bind(&website.section)
+            .bind(position)
+            .bind(owner)
+            .bind(&website.website_name)
+            .execute(&mut *transaction)
+            .await
+            .map_err(reorder_error)
+            .map_err(HomescreenError::from)
+            .inspect_err(|err| error!("Cannot reorder personal websites of {owner}, {err}"))?;
+        }
+
+        sqlx::query(
+            &self
+                .dialect
+                .sql("DELETE FROM moved_websites WHERE owner = ?"),
+        )
+        .bind(owner)
+        .execute(&mut *transaction)
+        .await
+        .map_err(ServerError::CannotUpdatePersonalHomescreen)?;
+        for (website_name, (section, position)) in &overlay.moved {
+            sqlx::query(&self.dialect.sql(
+                "INSERT INTO moved_websites(owner, website_name, section, position) VALUES(?, ?, ?, ?)",
+            ))
+            .bind(owner)
+            .bind(website_name)
+            .bind(section)
+            .bind(position)
+            .execute(&mut *transaction)
+            .await
+            .map_err(reorder_error)
+            .map_err(HomescreenError::from)
+            .inspect_err(|err| error!("Cannot reorder personal websites of {owner}, {err}"))?;
+        }
+
+        transaction
+            .commit()
+            .await
+            .map_err(ServerError::CannotUpdatePersonalHomescreen)?;
+
+        Ok(())
+    }
+}
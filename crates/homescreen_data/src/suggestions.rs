@@ -0,0 +1,167 @@
+use crate::{sections::SectionWebsites, websites::Website};
+use homescreen_errors::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A host from a browser's history that isn't on the homescreen yet
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct Suggestion {
+    website_name: String,
+    website_link: String,
+    /// The slug of the section it most likely belongs in, `None` when there are no sections
+    section: Option<String>,
+    visits: u64,
+}
+impl Suggestion {
+    pub fn name(&self) -> &str {
+        &self.website_name
+    }
+    pub fn link(&self) -> &str {
+        &self.website_link
+    }
+    pub fn section(&self) -> Option<&str> {
+        self.section.as_deref()
+    }
+    pub fn visits(&self) -> u64 {
+        self.visits
+    }
+    /// The website this suggestion becomes once it is accepted into `section`
+    pub fn accept(&self, section: String) -> HomescreenResult<Website> {
+        Website::new(
+            self.website_name.clone(),
+            self.website_link.clone(),
+            section,
+        )
+    }
+}
+
+/// How often each host was visited according to a browser's history
+#[derive(Clone, Default, Debug)]
+pub struct History {
+    visits: HashMap<String, u64>,
+}
+impl History {
+    /// Counts `visits` towards the host of `url`, anything that isn't a http or https url is
+    /// ignored
+    pub fn visit(&mut self, url: &str, visits: u64) {
+        if let Some(host) = Self::host(url) {
+            *self.visits.entry(host).or_default() += visits;
+        }
+    }
+    /// The host a url points to, without any `www.` in front of it
+    ///
+    /// ```rust
+    /// # use homescreen_data::prelude::*;
+    /// assert_eq!(History::host("https://www.GitHub.com:443/rust-lang"), Some("github.com".into()));
+    /// assert_eq!(History::host("http://docs.rs?query"), Some("docs.rs".into()));
+    /// assert_eq!(History::host("about:config"), None);
+    /// assert_eq!(History::host("https:///"), None);
+    /// ```
+    pub fn host(url: &str) -> Option<String> {
+        let rest = url
+            .strip_prefix("https://")
+            .or_else(|| url.strip_prefix("http://"))?;
+        let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+        let host = authority.rsplit('@').next().unwrap_or_default();
+        let host = host.split(':').next().unwrap_or_default().to_lowercase();
+        let host = host.strip_prefix("www.").map(str::to_owned).unwrap_or(host);
+
+        (!host.is_empty()).then_some(host)
+    }
+    /// The `limit` most visited hosts that no website on the homescreen links to yet, each one
+    /// goes in the section whose websites look the most like it. Suggestions are named after the
+    /// host instead when the usual name is already taken, and left out if that is taken as well
+    pub fn suggest(&self, columns: &[SectionWebsites], limit: usize) -> Vec<Suggestion> {
+        let existing: Vec<String> = columns
+            .iter()
+            .flat_map(|column| column.websites())
+            .filter_map(|website| Self::host(&website.url()))
+            .collect();
+        // Some databases compare names without caring about case so neither does this
+        let mut taken: Vec<String> = columns
+            .iter()
+            .flat_map(|column| column.websites())
+            .map(|website| website.name().to_lowercase())
+            .collect();
+
+        let mut visits: Vec<(&String, u64)> = self
+            .visits
+            .iter()
+            .filter(|(host, _)| !existing.contains(host))
+            .map(|(host, visits)| (host, *visits))
+            .collect();
+        visits.sort_by(|(a_host, a_visits), (b_host, b_visits)| {
+            b_visits.cmp(a_visits).then_with(|| a_host.cmp(b_host))
+        });
+
+        visits
+            .into_iter()
+            .filter_map(|(host, visits)| {
+                let website_name = [Self::name_from(host), host.clone()]
+                    .into_iter()
+                    .find(|name| !taken.contains(&name.to_lowercase()))?;
+                taken.push(website_name.to_lowercase());
+
+                Some(Suggestion {
+                    website_name,
+                    website_link: host.clone(),
+                    section: Self::section_for(host, columns),
+                    visits,
+                })
+            })
+            .take(limit)
+            .collect()
+    }
+    /// The first part of the host with a capital letter, `docs.github.com` becomes `Docs`
+    fn name_from(host: &str) -> String {
+        let label = host.split('.').next().unwrap_or(host);
+        let mut characters = label.chars();
+        characters
+            .next()
+            .map(|first| first.to_uppercase().chain(characters).collect())
+            .unwrap_or_default()
+    }
+    /// Websites on the same domain count the most, then websites sharing a word with the host in
+    /// their link, name or tags. When nothing matches the section with the most websites is used
+    fn section_for(host: &str, columns: &[SectionWebsites]) -> Option<String> {
+        let labels: Vec<&str> = host.split('.').collect();
+        let words: Vec<&str> = labels[..labels.len().saturating_sub(1)]
+            .iter()
+            .flat_map(|label| label.split('-'))
+            .filter(|word| word.len() >= 3)
+            .collect();
+
+        let similarity = |website: &Website| {
+            let link = Self::host(&website.url()).unwrap_or_default();
+            let shared_labels = labels
+                .iter()
+                .rev()
+                .zip(link.split('.').rev())
+                .take_while(|(a, b)| *a == b)
+                .count();
+
+            let name = website.name().to_lowercase();
+            let shared_words = words
+                .iter()
+                .filter(|word| {
+                    link.split(['.', '-'])
+                        .chain(name.split(|character: char| !character.is_alphanumeric()))
+                        .chain(website.tags().iter().flat_map(|tag| tag.split('-')))
+                        .any(|other| other == **word)
+                })
+                .count();
+
+            shared_labels.saturating_sub(1) * 2 + shared_words
+        };
+
+        columns
+            .iter()
+            .enumerate()
+            .max_by_key(|(index, column)| {
+                let score: usize = column.websites().iter().map(similarity).sum();
+                // Earlier sections win ties
+                (score, column.websites().len(), usize::MAX - index)
+            })
+            .map(|(_, column)| column.section().slug().to_owned())
+    }
+}
@@ -0,0 +1,109 @@
+use chrono::{DateTime, Utc};
+use homescreen_errors::prelude::*;
+#[cfg(feature = "poison_wasm")]
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "poison_wasm")]
+use sha2::{Digest, Sha256};
+use std::{fmt, str::FromStr};
+
+/// What a caller is allowed to do, each scope allows everything the ones before it do
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub enum Scope {
+    /// Looking at websites, sections and stats
+    Read,
+    /// Changing websites, sections and search engines
+    Write,
+    /// Managing who can use the backend
+    Admin,
+}
+impl Scope {
+    /// ```rust
+    /// # use homescreen_data::prelude::*;
+    /// assert!(Scope::Admin.allows(Scope::Write));
+    /// assert!(Scope::Write.allows(Scope::Write));
+    /// assert!(!Scope::Read.allows(Scope::Write));
+    /// ```
+    pub fn allows(self, required: Self) -> bool {
+        self >= required
+    }
+}
+impl FromStr for Scope {
+    type Err = HomescreenError;
+
+    /// ```rust
+    /// # use homescreen_data::prelude::*;
+    /// assert_eq!("write".parse::<Scope>().unwrap(), Scope::Write);
+    /// assert!("owner".parse::<Scope>().is_err());
+    /// ```
+    fn from_str(scope: &str) -> Result<Self, Self::Err> {
+        match scope {
+            "read" => Ok(Self::Read),
+            "write" => Ok(Self::Write),
+            "admin" => Ok(Self::Admin),
+            _ => Err(ServerError::CannotParseScope(scope.to_owned()).into()),
+        }
+    }
+}
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Read => "read",
+            Self::Write => "write",
+            Self::Admin => "admin",
+        })
+    }
+}
+
+/// A token that lets scripts use the backend, only a hash of the token itself is ever stored
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct ApiToken {
+    pub(crate) name: String,
+    pub(crate) scope: Scope,
+    pub(crate) created_at: DateTime<Utc>,
+}
+impl ApiToken {
+    pub fn new(name: String, scope: Scope, created_at: DateTime<Utc>) -> Self {
+        Self {
+            name,
+            scope,
+            created_at,
+        }
+    }
+    /// A new random token, this is what callers send and is only shown once
+    #[cfg(feature = "poison_wasm")]
+    pub fn generate() -> String {
+        random_secret("hst_")
+    }
+    /// What is stored in place of the token, tokens are random enough that a plain hash can't be
+    /// reversed
+    ///
+    /// ```rust
+    /// # use homescreen_data::prelude::*;
+    /// let token = ApiToken::generate();
+    /// assert_eq!(ApiToken::hash(&token), ApiToken::hash(&token));
+    /// assert_ne!(ApiToken::hash(&token), ApiToken::hash(&ApiToken::generate()));
+    /// ```
+    #[cfg(feature = "poison_wasm")]
+    pub fn hash(token: &str) -> String {
+        hex::encode(Sha256::digest(token.as_bytes()))
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn scope(&self) -> Scope {
+        self.scope
+    }
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+/// 32 random bytes as hex after `prefix`, which makes it easy to tell what a leaked secret is for
+#[cfg(feature = "poison_wasm")]
+pub(crate) fn random_secret(prefix: &str) -> String {
+    let mut bytes = [0; 32];
+    OsRng.fill_bytes(&mut bytes);
+    format!("{prefix}{}", hex::encode(bytes))
+}
@@ -0,0 +1,167 @@
+use crate::prelude::*;
+#[cfg(feature = "poison_wasm")]
+use argon2::{
+    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use chrono::{DateTime, Utc};
+use homescreen_errors::prelude::*;
+#[cfg(feature = "poison_wasm")]
+use rand::rngs::OsRng;
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+/// Passwords shorter than this are refused
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+
+/// What a user is allowed to do
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub enum Role {
+    /// Can only look at the homescreen, the same as not logging in
+    Viewer,
+    /// Can change anything, including who else can log in
+    Admin,
+}
+impl Role {
+    /// The api token scope with the same permissions
+    ///
+    /// ```rust
+    /// # use homescreen_data::prelude::*;
+    /// assert_eq!(Role::Viewer.scope(), Scope::Read);
+    /// assert!(Role::Admin.scope().allows(Scope::Write));
+    /// ```
+    pub fn scope(self) -> Scope {
+        match self {
+            Self::Viewer => Scope::Read,
+            Self::Admin => Scope::Admin,
+        }
+    }
+}
+impl FromStr for Role {
+    type Err = HomescreenError;
+
+    /// ```rust
+    /// # use homescreen_data::prelude::*;
+    /// assert_eq!("admin".parse::<Role>().unwrap(), Role::Admin);
+    /// assert!("owner".parse::<Role>().is_err());
+    /// ```
+    fn from_str(role: &str) -> Result<Self, Self::Err> {
+        match role {
+            "viewer" => Ok(Self::Viewer),
+            "admin" => Ok(Self::Admin),
+            _ => Err(ServerError::CannotParseRole(role.to_owned()).into()),
+        }
+    }
+}
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Viewer => "viewer",
+            Self::Admin => "admin",
+        })
+    }
+}
+
+/// Someone who can log in from a browser, their password is only ever stored hashed
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct User {
+    pub(crate) name: String,
+    pub(crate) role: Role,
+    pub(crate) created_at: DateTime<Utc>,
+}
+impl User {
+    pub fn new(name: String, role: Role, created_at: DateTime<Utc>) -> Self {
+        Self {
+            name,
+            role,
+            created_at,
+        }
+    }
+    /// Hashes `password` with argon2 and a random salt, the hash includes everything needed to
+    /// check a password against it later
+    ///
+    /// ```rust
+    /// # use homescreen_data::prelude::*;
+    /// let hash = User::hash_password("correct horse").unwrap();
+    /// assert!(User::verify_password("correct horse", &hash));
+    /// assert!(!User::verify_password("battery staple", &hash));
+    /// assert!(User::hash_password("short").is_err());
+    /// ```
+    #[cfg(feature = "poison_wasm")]
+    pub fn hash_password(password: &str) -> HomescreenResult<String> {
+        if password.chars().count() < MIN_PASSWORD_LENGTH {
+            return Err(ServerError::PasswordTooShort(MIN_PASSWORD_LENGTH).into());
+        }
+
+        Argon2::default()
+            .hash_password(password.as_bytes(), &SaltString::generate(&mut OsRng))
+            .map(|hash| hash.to_string())
+            .map_err(|err| ServerError::CannotHashPassword(err.to_string()).into())
+    }
+    /// Whether `password` is the one `password_hash` was made from
+    #[cfg(feature = "poison_wasm")]
+    pub fn verify_password(password: &str, password_hash: &str) -> bool {
+        PasswordHash::new(password_hash).is_ok_and(|hash| {
+            Argon2::default()
+                .verify_password(password.as_bytes(), &hash)
+                .is_ok()
+        })
+    }
+    /// A new random session for a browser that has just logged in, sessions are hashed with
+    /// [`ApiToken::hash`] the same as api tokens
+    #[cfg(feature = "poison_wasm")]
+    pub fn new_session() -> String {
+        crate::tokens::random_secret("hss_")
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn role(&self) -> Role {
+        self.role
+    }
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+/// Who made a request to the backend, either through an api token or by logging in
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct Caller {
+    pub(crate) name: String,
+    pub(crate) scope: Scope,
+    /// Whether they are one of the users here rather than an api token, only users have a
+    /// personal homescreen
+    #[serde(default)]
+    pub(crate) user: bool,
+}
+impl Caller {
+    pub fn new(name: String, scope: Scope) -> Self {
+        Self {
+            name,
+            scope,
+            user: false,
+        }
+    }
+    /// The user called `name`, rather than an api token
+    pub fn user(name: String, scope: Scope) -> Self {
+        Self {
+            name,
+            scope,
+            user: true,
+        }
+    }
+    /// Whether they can change websites, sections and search engines
+    pub fn can_edit(&self) -> bool {
+        self.scope.allows(Scope::Write)
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn scope(&self) -> Scope {
+        self.scope
+    }
+    pub fn is_user(&self) -> bool {
+        self.user
+    }
+}
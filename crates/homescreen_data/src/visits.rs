@@ -0,0 +1,67 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+/// How often a website was visited on a single day
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct DailyVisits {
+    day: NaiveDate,
+    visits: u64,
+}
+impl DailyVisits {
+    pub fn new(day: NaiveDate, visits: u64) -> Self {
+        Self { day, visits }
+    }
+    pub fn day(&self) -> NaiveDate {
+        self.day
+    }
+    pub fn visits(&self) -> u64 {
+        self.visits
+    }
+}
+
+/// Every visit made to a website through its go link
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct WebsiteStats {
+    website_name: String,
+    visits: u64,
+    last_visited: Option<DateTime<Utc>>,
+    /// Only days with at least one visit are included, earliest first
+    days: Vec<DailyVisits>,
+}
+impl WebsiteStats {
+    pub fn new(
+        website_name: String,
+        last_visited: Option<DateTime<Utc>>,
+        days: Vec<DailyVisits>,
+    ) -> Self {
+        Self {
+            website_name,
+            visits: days.iter().map(DailyVisits::visits).sum(),
+            last_visited,
+            days,
+        }
+    }
+    pub fn name(&self) -> &str {
+        &self.website_name
+    }
+    pub fn visits(&self) -> u64 {
+        self.visits
+    }
+    pub fn last_visited(&self) -> Option<DateTime<Utc>> {
+        self.last_visited
+    }
+    pub fn days(&self) -> &[DailyVisits] {
+        &self.days
+    }
+}
+
+/// The order websites are listed in within their section
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum WebsiteSort {
+    /// The order they were arranged in
+    #[default]
+    Position,
+    /// The most visited first, websites visited just as often keep their arranged order
+    MostUsed,
+}
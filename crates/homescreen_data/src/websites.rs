@@ -1,55 +1,43 @@
-// #[cfg(feature = "poison_wasm")]
+use crate::sections::WebsiteSection;
 use homescreen_errors::prelude::*;
-#[cfg(feature = "poison_wasm")]
-use log::error;
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "poison_wasm")]
-use sqlx::{prelude::FromRow, MySqlPool};
-#[cfg(feature = "poison_wasm")]
-use std::collections::HashMap;
-use std::str::FromStr;
-
-#[derive(Deserialize, Serialize, Hash, PartialEq, Eq, PartialOrd, Ord, Debug)]
-pub enum WebsiteSection {
-    Code,
-    Fun,
-    Editing,
-}
-impl ToString for WebsiteSection {
-    fn to_string(&self) -> String {
-        format!("{self:?}")
-    }
-}
-impl TryFrom<String> for WebsiteSection {
-    type Error = HomescreenError;
+use sqlx::prelude::FromRow;
+use std::collections::{BTreeMap, BTreeSet};
 
-    fn try_from(value: String) -> Result<Self, Self::Error> {
-        Self::from_str(&value)
-    }
-}
-impl FromStr for WebsiteSection {
-    type Err = HomescreenError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "code" => Ok(Self::Code),
-            "fun" => Ok(Self::Fun),
-            "editing" => Ok(Self::Editing),
-            _ => Err(ServerError::CannotParseWebsiteSection.into()),
-        }
-    }
-}
-impl WebsiteSection {
-    pub const ALL: [Self; 3] = [Self::Code, Self::Fun, Self::Editing];
+/// The names of websites in the order they should be shown, keyed by the slug of the section they
+/// should be shown in
+pub type WebsiteOrder = BTreeMap<String, Vec<String>>;
+
+/// Whether a write created a new website or changed an existing one
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum WebsiteChange {
+    Created,
+    Updated,
 }
 
 #[cfg_attr(feature = "poison_wasm", derive(FromRow))]
-#[derive(Deserialize, Serialize, PartialEq, Eq, Debug)]
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
 pub struct Website {
-    website_name: String,
-    website_link: String,
-    #[cfg_attr(feature = "poison_wasm", sqlx(try_from = "String"))]
-    section: WebsiteSection,
+    pub(crate) website_name: String,
+    pub(crate) website_link: String,
+    pub(crate) section: String,
+    /// Tags live in their own table so they are filled in separately
+    #[cfg_attr(feature = "poison_wasm", sqlx(skip))]
+    #[serde(default)]
+    pub(crate) tags: BTreeSet<String>,
+    /// A short name the website can be reached by from the search bar or `/go/{alias}`
+    #[serde(default)]
+    pub(crate) alias: Option<String>,
+    /// A link with numbered placeholders such as `github.com/rust-lang/{0}`, filled in with
+    /// whatever follows the name or alias
+    #[serde(default)]
+    pub(crate) link_template: Option<String>,
+    /// Only on someone's personal homescreen rather than the shared one, see
+    /// [`Overlay`](crate::prelude::Overlay)
+    #[cfg_attr(feature = "poison_wasm", sqlx(skip))]
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) personal: bool,
 }
 impl Website {
     pub fn new(
@@ -61,10 +49,86 @@ impl Website {
             Ok(Self {
                 website_name,
                 website_link,
-                section: section.try_into()?,
+                section: WebsiteSection::validate_slug(section)?,
+                tags: BTreeSet::new(),
+                alias: None,
+                link_template: None,
+                personal: false,
             })
         })
     }
+    /// Replaces the tags of the website, duplicates are only kept once
+    pub fn with_tags(mut self, tags: impl IntoIterator<Item = String>) -> HomescreenResult<Self> {
+        self.tags = tags
+            .into_iter()
+            .map(Self::validate_tag)
+            .collect::<HomescreenResult<_>>()?;
+        Ok(self)
+    }
+    /// Replaces the alias of the website, `None` removes it
+    pub fn with_alias(mut self, alias: Option<String>) -> HomescreenResult<Self> {
+        self.alias = alias.map(Self::validate_alias).transpose()?;
+        Ok(self)
+    }
+    /// Replaces the link template of the website, `None` removes it
+    pub fn with_link_template(mut self, link_template: Option<String>) -> HomescreenResult<Self> {
+        self.link_template = link_template
+            .map(Self::validate_link_template)
+            .transpose()?;
+        Ok(self)
+    }
+    /// Aliases are typed into the search bar so they follow the same rules as section slugs
+    ///
+    /// ```rust
+    /// # use homescreen_data::prelude::*;
+    /// assert!(Website::validate_alias("gh".into()).is_ok());
+    /// assert!(Website::validate_alias("".into()).is_err());
+    /// assert!(Website::validate_alias("git hub".into()).is_err());
+    /// ```
+    pub fn validate_alias(alias: String) -> HomescreenResult<String> {
+        WebsiteSection::validate_slug(alias).map_err(|_| ServerError::CannotParseAlias.into())
+    }
+    /// Templates are links without `http://` or `https://` whose placeholders are numbers in
+    /// braces
+    ///
+    /// ```rust
+    /// # use homescreen_data::prelude::*;
+    /// assert!(Website::validate_link_template("github.com/rust-lang/{0}".into()).is_ok());
+    /// assert!(Website::validate_link_template("github.com/{0}/{1}".into()).is_ok());
+    /// assert!(Website::validate_link_template("github.com/{name}".into()).is_err());
+    /// assert!(Website::validate_link_template("github.com/{0".into()).is_err());
+    /// assert!(Website::validate_link_template("https://github.com/{0}".into()).is_err());
+    /// ```
+    pub fn validate_link_template(link_template: String) -> HomescreenResult<String> {
+        let link_template = Self::validate_link(link_template)
+            .map_err(|_| HomescreenError::from(ServerError::CannotParseLinkTemplate))?;
+
+        let mut rest = link_template.as_str();
+        while let Some(start) = rest.find(['{', '}']) {
+            let placeholder = rest[start..]
+                .strip_prefix('{')
+                .and_then(|placeholder| placeholder.split_once('}'))
+                .filter(|(index, _)| !index.is_empty() && index.parse::<usize>().is_ok());
+            match placeholder {
+                Some((_, after)) => rest = after,
+                None => return Err(ServerError::CannotParseLinkTemplate.into()),
+            }
+        }
+
+        Ok(link_template)
+    }
+    /// Tags follow the same rules as section slugs so they can be used in urls
+    ///
+    /// ```rust
+    /// # use homescreen_data::prelude::*;
+    /// assert!(Website::validate_tag("rust".into()).is_ok());
+    /// assert!(Website::validate_tag("ci-cd".into()).is_ok());
+    /// assert!(Website::validate_tag("".into()).is_err());
+    /// assert!(Website::validate_tag("Rust Docs".into()).is_err());
+    /// ```
+    pub fn validate_tag(tag: String) -> HomescreenResult<String> {
+        WebsiteSection::validate_slug(tag).map_err(|_| ServerError::CannotParseTag.into())
+    }
     pub fn validate_link(website_link: String) -> HomescreenResult<String> {
         let protocol_specified =
             website_link.starts_with("https://") || website_link.starts_with("http://");
@@ -81,91 +145,253 @@ impl Website {
     pub fn link(&self) -> &str {
         &self.website_link
     }
+    /// The link with the `https://` it is stored without
+    pub fn url(&self) -> String {
+        format!("https://{}", self.website_link)
+    }
+    /// Where the homepage links to so the visit is counted before being sent on to the website,
+    /// the name is percent encoded so it can be used as a single path segment
+    ///
+    /// ```rust
+    /// # use homescreen_data::prelude::*;
+    /// let website = Website::new("Docs.rs".into(), "docs.rs".into(), "code".into()).unwrap();
+    /// assert_eq!(website.go_link(), "/go/Docs.rs");
+    ///
+    /// let website = Website::new("Hacker News?".into(), "news.ycombinator.com".into(), "fun".into()).unwrap();
+    /// assert_eq!(website.go_link(), "/go/Hacker%20News%3F");
+    /// ```
+    pub fn go_link(&self) -> String {
+        format!("/go/{}", encode_segment(&self.website_name))
+    }
+    /// The go link that fills the link template in with `arguments`
+    ///
+    /// ```rust
+    /// # use homescreen_data::prelude::*;
+    /// let website = Website::new("GitHub".into(), "github.com".into(), "code".into()).unwrap();
+    /// assert_eq!(website.go_link_with(&["rust-lang", "rust"]), "/go/GitHub/rust-lang/rust");
+    /// assert_eq!(website.go_link_with(&["a/b c"]), "/go/GitHub/a%2Fb%20c");
+    /// ```
+    pub fn go_link_with(&self, arguments: &[&str]) -> String {
+        arguments.iter().fold(self.go_link(), |link, argument| {
+            format!("{link}/{}", encode_segment(argument))
+        })
+    }
+    /// The link with the template filled in with `arguments`, a placeholder without an argument is
+    /// left empty and any arguments beyond the last placeholder are added to it separated by
+    /// spaces. `None` if the website has no template
+    ///
+    /// ```rust
+    /// # use homescreen_data::prelude::*;
+    /// let website = Website::new("GitHub".into(), "github.com".into(), "code".into())
+    ///     .unwrap()
+    ///     .with_link_template(Some("github.com/{0}/{1}".into()))
+    ///     .unwrap();
+    /// assert_eq!(website.expand(&["rust-lang", "rust"]).unwrap(), "https://github.com/rust-lang/rust");
+    /// assert_eq!(website.expand(&["rust-lang"]).unwrap(), "https://github.com/rust-lang/");
+    ///
+    /// let website = Website::new("Docs".into(), "docs.rs".into(), "code".into())
+    ///     .unwrap()
+    ///     .with_link_template(Some("docs.rs/releases/search?query={0}".into()))
+    ///     .unwrap();
+    /// assert_eq!(website.expand(&["serde", "json"]).unwrap(), "https://docs.rs/releases/search?query=serde%20json");
+    /// ```
+    pub fn expand(&self, arguments: &[&str]) -> Option<String> {
+        let template = self.link_template.as_deref()?;
+
+        let mut placeholders = Vec::new();
+        let mut rest = template;
+        while let Some((before, after)) = rest.split_once('{') {
+            let (index, after) = after.split_once('}')?;
+            placeholders.push((before, index.parse::<usize>().ok()?));
+            rest = after;
+        }
+
+        let last = placeholders.iter().map(|(_, index)| *index).max();
+        let argument = |index: usize| match Some(index) == last {
+            true => arguments.get(index..).unwrap_or_default().join(" "),
+            false => arguments.get(index).copied().unwrap_or_default().to_owned(),
+        };
+
+        let mut link = String::from("https://");
+        for (before, index) in placeholders {
+            link.push_str(before);
+            link.push_str(&encode_segment(&argument(index)));
+        }
+        link.push_str(rest);
+        Some(link)
+    }
     pub fn icon_link(&self) -> String {
         format!("https://icons.duckduckgo.com/ip3/{}.ico", self.website_link)
     }
-    pub fn section(&self) -> &WebsiteSection {
+    /// The slug of the section this website belongs to
+    pub fn section(&self) -> &str {
         &self.section
     }
+    pub fn tags(&self) -> &BTreeSet<String> {
+        &self.tags
+    }
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.contains(tag)
+    }
+    /// How well `query` matches the name, link or tags of the website, `None` if it doesn't.
+    /// Every character of the query has to appear in order, matches next to each other or at the
+    /// start of a word count for more
+    ///
+    /// ```rust
+    /// # use homescreen_data::prelude::*;
+    /// let website = Website::new("GitHub".into(), "github.com".into(), "code".into())
+    ///     .unwrap()
+    ///     .with_tags(["forge".into()])
+    ///     .unwrap();
+    /// assert!(website.fuzzy_score("gthb").is_some());
+    /// assert!(website.fuzzy_score("Hub").is_some());
+    /// assert!(website.fuzzy_score("com").is_some());
+    /// assert!(website.fuzzy_score("frg").is_some());
+    /// assert!(website.fuzzy_score("gitlab").is_none());
+    /// assert!(website.fuzzy_score("github") > website.fuzzy_score("gthb"));
+    /// ```
+    pub fn fuzzy_score(&self, query: &str) -> Option<u32> {
+        [&self.website_name, &self.website_link]
+            .into_iter()
+            .chain(&self.tags)
+            .filter_map(|candidate| fuzzy_score(candidate, query))
+            .max()
+    }
+    pub fn alias(&self) -> Option<&str> {
+        self.alias.as_deref()
+    }
+    pub fn link_template(&self) -> Option<&str> {
+        self.link_template.as_deref()
+    }
+    pub fn is_personal(&self) -> bool {
+        self.personal
+    }
 }
 
-#[cfg(feature = "poison_wasm")]
-impl Website {
-    pub async fn get_websites(
-        database: &MySqlPool,
-    ) -> HomescreenResult<HashMap<WebsiteSection, Box<[Website]>>> {
-        let coding = Self::get_coding_websites(database).await?;
-        let fun = Self::get_fun_websites(database).await?;
-        let editing = Self::get_editing_websites(database).await?;
-
-        let mut websites = HashMap::new();
-
-        websites.insert(WebsiteSection::Code, coding);
-        websites.insert(WebsiteSection::Fun, fun);
-        websites.insert(WebsiteSection::Editing, editing);
-
-        Ok(websites)
-    }
-    pub async fn get_coding_websites(database: &MySqlPool) -> HomescreenResult<Box<[Self]>> {
-        sqlx::query_as(
-            "SELECT website_name, website_link, section FROM websites WHERE section = 'code'",
-        )
-        .fetch_all(database)
-        .await
-        .map_err(ServerError::CannotRetrieveWebsites)
-        .map_err(HomescreenError::from)
-        .inspect_err(|err| error!("Cannot retrieve coding websites, {err}"))
-        .map(Vec::into_boxed_slice)
-    }
-    pub async fn get_fun_websites(database: &MySqlPool) -> HomescreenResult<Box<[Self]>> {
-        sqlx::query_as(
-            "SELECT website_name, website_link, section FROM websites WHERE section = 'fun'",
-        )
-        .fetch_all(database)
-        .await
-        .map_err(ServerError::CannotRetrieveWebsites)
-        .map_err(HomescreenError::from)
-        .inspect_err(|err| error!("Cannot retrieve fun websites, {err}"))
-        .map(Vec::into_boxed_slice)
-    }
-    pub async fn get_editing_websites(database: &MySqlPool) -> HomescreenResult<Box<[Self]>> {
-        sqlx::query_as(
-            "SELECT website_name, website_link, section FROM websites WHERE section = 'editing'",
-        )
-        .fetch_all(database)
-        .await
-        .map_err(ServerError::CannotRetrieveWebsites)
-        .map_err(HomescreenError::from)
-        .inspect_err(|err| error!("Cannot retrieve editing websites, {err}"))
-        .map(Vec::into_boxed_slice)
-    }
-    pub async fn create_or_update_website(self, database: &MySqlPool) -> HomescreenResult {
-        sqlx::query("INSERT INTO websites(website_name, website_link, section) VALUES(?, ?, ?)")
-            .bind(self.website_name)
-            .bind(self.website_link)
-            .bind(self.section.to_string().to_lowercase())
-            .execute(database)
-            .await
-            .map_err(ServerError::CannotInsertWebsite)
-            .map_err(HomescreenError::from)
-            .inspect_err(|err| error!("Cannot insert website, {err}"))?;
-
-        Ok(())
-    }
-    pub async fn delete_websites(website_name: &str, database: &MySqlPool) -> HomescreenResult {
-        sqlx::query("DELETE FROM websites WHERE website_name = ?")
-            .bind(website_name)
-            .execute(database)
-            .await
-            .map_err(ServerError::CannotDeleteWebsite)
-            .and_then(|rows| {
-                if rows.rows_affected() == 0 {
-                    Err(ServerError::CannotDeleteExistingWebsite)
-                } else {
-                    Ok(())
-                }
-            })
-            .map_err(HomescreenError::from)
-            .inspect_err(|err| error!("Cannot delete website, {err}"))
+/// The parts of a website to change, anything that isn't set is left as it is
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct WebsitePatch {
+    pub(crate) website_name: Option<String>,
+    pub(crate) website_link: Option<String>,
+    pub(crate) section: Option<String>,
+    pub(crate) tags: Option<Vec<String>>,
+    pub(crate) alias: Option<Option<String>>,
+    pub(crate) link_template: Option<Option<String>>,
+}
+impl WebsitePatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Renames the website
+    pub fn with_name(mut self, website_name: String) -> Self {
+        self.website_name = Some(website_name);
+        self
+    }
+    pub fn with_link(mut self, website_link: String) -> Self {
+        self.website_link = Some(website_link);
+        self
+    }
+    pub fn with_section(mut self, section: String) -> Self {
+        self.section = Some(section);
+        self
+    }
+    /// Replaces every tag, an empty list removes them all
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = Some(tags);
+        self
+    }
+    /// Replaces the alias, `None` removes it
+    pub fn with_alias(mut self, alias: Option<String>) -> Self {
+        self.alias = Some(alias);
+        self
+    }
+    /// Replaces the link template, `None` removes it
+    pub fn with_link_template(mut self, link_template: Option<String>) -> Self {
+        self.link_template = Some(link_template);
+        self
+    }
+    /// Applies the patch to `website`, checking every changed part the same way the forms are
+    pub(crate) fn apply(self, website: Website) -> HomescreenResult<Website> {
+        let website_link = self
+            .website_link
+            .map(Website::validate_link)
+            .transpose()?
+            .unwrap_or(website.website_link);
+        let section = self
+            .section
+            .map(WebsiteSection::validate_slug)
+            .transpose()?
+            .unwrap_or(website.section);
+        let website = Website {
+            website_name: self.website_name.unwrap_or(website.website_name),
+            website_link,
+            section,
+            ..website
+        };
+
+        let website = match self.tags {
+            Some(tags) => website.with_tags(tags)?,
+            None => website,
+        };
+        let website = match self.alias {
+            Some(alias) => website.with_alias(alias)?,
+            None => website,
+        };
+        match self.link_template {
+            Some(link_template) => website.with_link_template(link_template),
+            None => Ok(website),
+        }
+    }
+    pub(crate) fn changes_tags(&self) -> bool {
+        self.tags.is_some()
+    }
+}
+
+/// Scores `candidate` against `query` ignoring case and whitespace, each matched character is
+/// worth one more if it follows the previous match and two more if it starts a word
+fn fuzzy_score(candidate: &str, query: &str) -> Option<u32> {
+    let mut candidate = candidate.chars().flat_map(char::to_lowercase).enumerate();
+    let mut score = 0;
+    let mut previous: Option<usize> = None;
+    let mut last_seen: Option<char> = None;
+
+    for wanted in query
+        .chars()
+        .filter(|char| !char.is_whitespace())
+        .flat_map(char::to_lowercase)
+    {
+        loop {
+            let (index, char) = candidate.next()?;
+            let before = last_seen.replace(char);
+            if char != wanted {
+                continue;
+            }
+
+            score += 1;
+            if previous.is_some_and(|previous| previous + 1 == index) {
+                score += 1;
+            }
+            if before.is_none_or(|before| !before.is_alphanumeric()) {
+                score += 2;
+            }
+            previous = Some(index);
+            break;
+        }
+    }
+
+    Some(score)
+}
+
+/// Percent encodes everything but the characters that are always allowed in a url
+pub(crate) fn encode_segment(segment: &str) -> String {
+    let mut encoded = String::with_capacity(segment.len());
+    for byte in segment.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
     }
+    encoded
 }
@@ -1,14 +1,17 @@
 #[cfg(feature = "poison_wasm")]
 use actix_web::{
     body::BoxBody,
-    http::{header::ContentType, StatusCode},
+    http::{
+        header::{ContentType, WWW_AUTHENTICATE},
+        StatusCode,
+    },
     HttpResponse, ResponseError,
 };
 use reqwest::Error as ReqwestError;
-use sqlx::Error as SqlxError;
+use sqlx::{migrate::MigrateError, Error as SqlxError};
 use std::io::Error as IoError;
 use thiserror::Error as ThisError;
-use toml::de::Error as DeError;
+use toml::{de::Error as DeError, ser::Error as SerError};
 
 #[derive(ThisError, Debug)]
 pub enum HomescreenError {
@@ -32,7 +35,13 @@ impl ResponseError for HomescreenError {
     }
 
     fn error_response(&self) -> HttpResponse<BoxBody> {
-        HttpResponse::build(self.status_code())
+        let mut response = HttpResponse::build(self.status_code());
+        // Tells clients how they are meant to authenticate
+        if self.status_code() == StatusCode::UNAUTHORIZED {
+            response.insert_header((WWW_AUTHENTICATE, "Bearer"));
+        }
+
+        response
             .content_type(ContentType::plaintext())
             .body(format!("{self:?}"))
     }
@@ -44,6 +53,18 @@ pub enum ConfigError {
     CannotFindConfigFile(#[source] IoError),
     #[error("Cannot parse Config.toml, please check format, why: {0}")]
     CannotParseConfigFile(#[source] DeError),
+    #[error("Cannot read sites file {1}, why: {0}")]
+    CannotFindSitesFile(#[source] IoError, String),
+    #[error("Cannot parse sites file {1}, please check format, why: {0}")]
+    CannotParseSitesFile(#[source] DeError, String),
+    #[error("{0} is declared more than once")]
+    DuplicateSite(String),
+    #[error("Cannot sync website {0}, {1} already uses the same link and isn't declared")]
+    SiteLinkAlreadyUsed(String, String),
+    #[error("Cannot sync website {0}, {1} already uses the same alias and isn't declared")]
+    SiteAliasAlreadyUsed(String, String),
+    #[error("Cannot sync website {0}, someone already has a personal website with the same name")]
+    SiteNameUsedByPersonalWebsite(String),
 }
 
 #[derive(ThisError, Debug)]
@@ -54,6 +75,24 @@ pub enum StartupError {
     CannotStartServer(#[source] IoError),
     #[error("Cannot connect to database with url {1}, why: {0}")]
     CannotConnectToDatabase(#[source] SqlxError, String),
+    #[error(
+        "Unsupported database url {0}, it should start with mysql://, sqlite:// or postgres://"
+    )]
+    UnsupportedDatabaseUrl(String),
+    #[error("Cannot apply database migrations, why: {0}")]
+    CannotMigrateDatabase(#[source] MigrateError),
+    #[error("The database is at schema version {0} but this backend only knows up to version {1}, please update the backend")]
+    DatabaseSchemaTooNew(i64, i64),
+    #[error("Unknown argument {0}, the supported arguments are --migrate-only, --create-token <name> [--scope <scope>] (which writes the secret to stdout) and --create-user <name> [--role <role>]")]
+    UnknownArgument(String),
+    #[error("Cannot read password from stdin, why: {0}")]
+    CannotReadPassword(#[source] IoError),
+    #[error("Cannot write api token to stdout, why: {0}")]
+    CannotWriteToken(#[source] IoError),
+    #[error("The argument {0} needs a value")]
+    MissingArgumentValue(String),
+    #[error("Cannot discover identity provider {0}, why: {1}")]
+    CannotDiscoverOidcProvider(String, String),
 }
 
 #[derive(ThisError, Debug)]
@@ -64,12 +103,134 @@ pub enum ServerError {
     CannotInsertWebsite(#[source] SqlxError),
     #[error("Cannot delete website, why: {0}")]
     CannotDeleteWebsite(#[source] SqlxError),
+    #[error("Cannot update website, why: {0}")]
+    CannotUpdateWebsite(#[source] SqlxError),
+    #[error("Cannot find website")]
+    CannotFindWebsite,
+    #[error("A website with that name already exists")]
+    WebsiteAlreadyExists,
+    #[error("Another website already uses that link")]
+    WebsiteLinkAlreadyExists,
+    #[error("Cannot reorder websites, why: {0}")]
+    CannotReorderWebsites(#[source] SqlxError),
+    #[error("Tags may only contain lowercase letters, digits, - and _")]
+    CannotParseTag,
+    #[error("Aliases may only contain lowercase letters, digits, - and _")]
+    CannotParseAlias,
+    #[error("Link templates may not include the transfer protocol and placeholders must be numbers in braces such as {{0}}")]
+    CannotParseLinkTemplate,
+    #[error("Another website already uses that alias")]
+    WebsiteAliasAlreadyExists,
+    #[error("Cannot parse backup, why: {0}")]
+    CannotParseBackup(String),
+    #[error("Cannot write backup, why: {0}")]
+    CannotSerializeBackup(#[source] SerError),
+    #[error("Backups from version {0} are newer than this version of the backend can read")]
+    UnsupportedBackupVersion(u32),
+    #[error("Cannot import backup, why: {0}")]
+    CannotImportBackup(#[source] SqlxError),
+    #[error("Cannot sync sites, why: {0}")]
+    CannotSyncSites(#[source] SqlxError),
+    #[error("Cannot record visit, why: {0}")]
+    CannotRecordVisit(#[source] SqlxError),
+    #[error("Cannot retrieve visits, why: {0}")]
+    CannotRetrieveVisits(#[source] SqlxError),
+    #[error("Cannot parse bookmarks, why: {0}")]
+    CannotParseBookmarks(String),
+    #[error("Cannot import bookmarks, why: {0}")]
+    CannotImportBookmarks(#[source] SqlxError),
+    #[error("Cannot store uploaded file, why: {0}")]
+    CannotStoreUpload(#[source] IoError),
+    #[error("Cannot read browser database, why: {0}")]
+    CannotReadBrowserDatabase(#[source] SqlxError),
     #[error("Unable to delete website that already exists")]
     CannotDeleteExistingWebsite,
-    #[error("Cannot parse website section")]
+    #[error(
+        "Cannot parse website section, slugs may only contain lowercase letters, digits, - and _"
+    )]
     CannotParseWebsiteSection,
+    #[error("Cannot retrieve sections, why: {0}")]
+    CannotRetrieveSections(#[source] SqlxError),
+    #[error("Cannot insert section, why: {0}")]
+    CannotInsertSection(#[source] SqlxError),
+    #[error("Cannot update section, why: {0}")]
+    CannotUpdateSection(#[source] SqlxError),
+    #[error("Cannot delete section, why: {0}")]
+    CannotDeleteSection(#[source] SqlxError),
+    #[error("Cannot find section")]
+    CannotFindSection,
+    #[error("A section with that slug already exists")]
+    SectionAlreadyExists,
+    #[error("Unable to delete section that still contains websites")]
+    SectionIsNotEmpty,
     #[error("Website link includes transfer protocol")]
     WebsiteLinkIncludesTransferProtocol,
+    #[error("Cannot retrieve search engines, why: {0}")]
+    CannotRetrieveSearchEngines(#[source] SqlxError),
+    #[error("Cannot save search engine, why: {0}")]
+    CannotSaveSearchEngine(#[source] SqlxError),
+    #[error("Cannot delete search engine, why: {0}")]
+    CannotDeleteSearchEngine(#[source] SqlxError),
+    #[error("Cannot find search engine")]
+    CannotFindSearchEngine,
+    #[error("Another search engine already uses that prefix")]
+    SearchPrefixAlreadyExists,
+    #[error("Search engine urls must start with http:// or https:// and include {{query}}")]
+    CannotParseSearchUrl,
+    #[error("Search prefixes must be ! followed by lowercase letters, digits, - and _")]
+    CannotParseSearchPrefix,
+    #[error("Cannot retrieve api tokens, why: {0}")]
+    CannotRetrieveTokens(#[source] SqlxError),
+    #[error("Cannot insert api token, why: {0}")]
+    CannotInsertToken(#[source] SqlxError),
+    #[error("Cannot delete api token, why: {0}")]
+    CannotDeleteToken(#[source] SqlxError),
+    #[error("Cannot find api token")]
+    CannotFindToken,
+    #[error("An api token with that name already exists")]
+    TokenAlreadyExists,
+    #[error("Unknown scope {0}, it should be read, write or admin")]
+    CannotParseScope(String),
+    #[error("Missing or invalid credentials")]
+    Unauthorized,
+    #[error("Not allowed to {0}, it needs the {1} scope")]
+    Forbidden(String, String),
+    #[error("Cannot retrieve users, why: {0}")]
+    CannotRetrieveUsers(#[source] SqlxError),
+    #[error("Cannot save user, why: {0}")]
+    CannotSaveUser(#[source] SqlxError),
+    #[error("Cannot delete user, why: {0}")]
+    CannotDeleteUser(#[source] SqlxError),
+    #[error("Cannot find user")]
+    CannotFindUser,
+    #[error("There is already a user called {0}")]
+    UserAlreadyExists(String),
+    #[error("Unknown role {0}, it should be viewer or admin")]
+    CannotParseRole(String),
+    #[error("Passwords need to be at least {0} characters long")]
+    PasswordTooShort(usize),
+    #[error("Cannot hash password, why: {0}")]
+    CannotHashPassword(String),
+    #[error("Wrong name or password")]
+    InvalidLogin,
+    #[error("Cannot create session, why: {0}")]
+    CannotCreateSession(#[source] SqlxError),
+    #[error("Cannot retrieve session, why: {0}")]
+    CannotRetrieveSession(#[source] SqlxError),
+    #[error("Cannot delete session, why: {0}")]
+    CannotDeleteSession(#[source] SqlxError),
+    #[error("Logging in with an identity provider isn't configured")]
+    OidcNotConfigured,
+    #[error("Cannot log in with the identity provider, why: {0}")]
+    OidcLoginFailed(String),
+    #[error("The {0} header is only accepted from trusted proxies")]
+    UntrustedProxy(String),
+    #[error("Only users have a personal homescreen")]
+    NoPersonalHomescreen,
+    #[error("Cannot retrieve personal homescreen, why: {0}")]
+    CannotRetrievePersonalHomescreen(#[source] SqlxError),
+    #[error("Cannot update personal homescreen, why: {0}")]
+    CannotUpdatePersonalHomescreen(#[source] SqlxError),
 }
 #[cfg(feature = "poison_wasm")]
 impl ResponseError for ServerError {
@@ -78,9 +239,69 @@ impl ResponseError for ServerError {
             Self::CannotInsertWebsite(_) => StatusCode::INTERNAL_SERVER_ERROR,
             Self::CannotRetrieveWebsites(_) => StatusCode::INTERNAL_SERVER_ERROR,
             Self::CannotDeleteWebsite(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::CannotUpdateWebsite(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::CannotFindWebsite => StatusCode::NOT_FOUND,
+            Self::WebsiteAlreadyExists => StatusCode::CONFLICT,
+            Self::WebsiteLinkAlreadyExists => StatusCode::CONFLICT,
+            Self::CannotReorderWebsites(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::CannotParseTag => StatusCode::BAD_REQUEST,
+            Self::CannotParseAlias => StatusCode::BAD_REQUEST,
+            Self::CannotParseLinkTemplate => StatusCode::BAD_REQUEST,
+            Self::WebsiteAliasAlreadyExists => StatusCode::CONFLICT,
+            Self::CannotParseBackup(_) => StatusCode::BAD_REQUEST,
+            Self::CannotSerializeBackup(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::UnsupportedBackupVersion(_) => StatusCode::BAD_REQUEST,
+            Self::CannotImportBackup(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::CannotSyncSites(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::CannotRecordVisit(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::CannotRetrieveVisits(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::CannotParseBookmarks(_) => StatusCode::BAD_REQUEST,
+            Self::CannotImportBookmarks(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::CannotStoreUpload(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::CannotReadBrowserDatabase(_) => StatusCode::BAD_REQUEST,
             Self::CannotDeleteExistingWebsite => StatusCode::BAD_REQUEST,
             Self::WebsiteLinkIncludesTransferProtocol => StatusCode::BAD_REQUEST,
             Self::CannotParseWebsiteSection => StatusCode::BAD_REQUEST,
+            Self::CannotRetrieveSections(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::CannotInsertSection(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::CannotUpdateSection(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::CannotDeleteSection(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::CannotFindSection => StatusCode::NOT_FOUND,
+            Self::SectionAlreadyExists => StatusCode::CONFLICT,
+            Self::SectionIsNotEmpty => StatusCode::CONFLICT,
+            Self::CannotRetrieveSearchEngines(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::CannotSaveSearchEngine(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::CannotDeleteSearchEngine(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::CannotFindSearchEngine => StatusCode::NOT_FOUND,
+            Self::SearchPrefixAlreadyExists => StatusCode::CONFLICT,
+            Self::CannotParseSearchUrl => StatusCode::BAD_REQUEST,
+            Self::CannotParseSearchPrefix => StatusCode::BAD_REQUEST,
+            Self::CannotRetrieveTokens(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::CannotInsertToken(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::CannotDeleteToken(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::CannotFindToken => StatusCode::NOT_FOUND,
+            Self::TokenAlreadyExists => StatusCode::CONFLICT,
+            Self::CannotParseScope(_) => StatusCode::BAD_REQUEST,
+            Self::Unauthorized => StatusCode::UNAUTHORIZED,
+            Self::Forbidden(_, _) => StatusCode::FORBIDDEN,
+            Self::CannotRetrieveUsers(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::CannotSaveUser(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::CannotDeleteUser(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::CannotFindUser => StatusCode::NOT_FOUND,
+            Self::UserAlreadyExists(_) => StatusCode::CONFLICT,
+            Self::CannotParseRole(_) => StatusCode::BAD_REQUEST,
+            Self::PasswordTooShort(_) => StatusCode::BAD_REQUEST,
+            Self::CannotHashPassword(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::InvalidLogin => StatusCode::UNAUTHORIZED,
+            Self::CannotCreateSession(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::CannotRetrieveSession(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::CannotDeleteSession(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::OidcNotConfigured => StatusCode::NOT_FOUND,
+            Self::OidcLoginFailed(_) => StatusCode::UNAUTHORIZED,
+            Self::UntrustedProxy(_) => StatusCode::UNAUTHORIZED,
+            Self::NoPersonalHomescreen => StatusCode::FORBIDDEN,
+            Self::CannotRetrievePersonalHomescreen(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::CannotUpdatePersonalHomescreen(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
 }
@@ -91,4 +312,22 @@ pub enum FrontendError {
     CannotRetrieveWebsites(#[source] ReqwestError),
     #[error("Cannot parse websites, why: {0}")]
     InvalidResponseRecieved(#[source] ReqwestError),
+    #[error("Cannot reorder websites, why: {0}")]
+    CannotReorderWebsites(#[source] ReqwestError),
+    #[error("Cannot suggest websites, why: {0}")]
+    CannotSuggestWebsites(#[source] ReqwestError),
+    #[error("Cannot save website, why: {0}")]
+    CannotSaveWebsite(#[source] ReqwestError),
+    #[error("Cannot retrieve search engines, why: {0}")]
+    CannotRetrieveSearchEngines(#[source] ReqwestError),
+    #[error("Cannot log in, why: {0}")]
+    CannotLogIn(#[source] ReqwestError),
+    #[error("Cannot log out, why: {0}")]
+    CannotLogOut(#[source] ReqwestError),
+    #[error("Cannot retrieve session, why: {0}")]
+    CannotRetrieveSession(#[source] ReqwestError),
+    #[error("Cannot retrieve single sign-on, why: {0}")]
+    CannotRetrieveSingleSignOn(#[source] ReqwestError),
+    #[error("Nobody is logged in")]
+    NotLoggedIn,
 }
@@ -0,0 +1,106 @@
+use dioxus::prelude::*;
+use homescreen_data::prelude::*;
+use homescreen_server_functions::prelude::{get_session, get_single_sign_on, log_in, log_out};
+
+/// Whoever is logged in, anything for editing is only shown when they can edit
+pub fn can_edit(caller: Signal<Option<Caller>>) -> bool {
+    caller.read().as_ref().is_some_and(Caller::can_edit)
+}
+
+/// Logging in and out, the session itself is kept in a cookie the backend sets
+pub fn Account() -> Element {
+    let response = use_server_future(get_session)?;
+    let single_sign_on = use_server_future(get_single_sign_on)?;
+    let mut caller = use_context::<Signal<Option<Caller>>>();
+    let mut error = use_signal(|| None::<String>);
+
+    use_effect(move || {
+        if let Some(Ok(session)) = &*response.read() {
+            caller.set(session.clone());
+        }
+    });
+
+    let current = caller.read().clone();
+    rsx!(
+        section {
+            id: "account",
+            h2 {
+                class: "sr-only",
+                { "Account" }
+            }
+            match current {
+                Some(current) => rsx!(
+                    span {
+                        { format!("Logged in as {}", current.name()) }
+                    }
+                    button {
+                        r#type: "button",
+                        onclick: move |_| async move {
+                            match log_out().await {
+                                Ok(()) => {
+                                    error.set(None);
+                                    caller.set(None);
+                                }
+                                Err(err) => {
+                                    log::error!("{err}");
+                                    error.set(Some(err.to_string()));
+                                }
+                            }
+                        },
+                        "Log out"
+                    }
+                ),
+                None => rsx!(
+                    form {
+                        prevent_default: "onsubmit",
+                        onsubmit: move |event: FormEvent| async move {
+                            let values = event.values();
+                            let value = |name: &str| {
+                                values.get(name).map(FormValue::as_value).unwrap_or_default()
+                            };
+
+                            match log_in(value("name"), value("password")).await {
+                                Ok(logged_in) => {
+                                    error.set(None);
+                                    caller.set(Some(logged_in));
+                                }
+                                Err(err) => {
+                                    log::error!("{err}");
+                                    error.set(Some(err.to_string()));
+                                }
+                            }
+                        },
+                        input {
+                            name: "name",
+                            r#type: "text",
+                            placeholder: "Name",
+                            autocomplete: "username",
+                        }
+                        input {
+                            name: "password",
+                            r#type: "password",
+                            placeholder: "Password",
+                            autocomplete: "current-password",
+                        }
+                        button {
+                            r#type: "submit",
+                            "Log in"
+                        }
+                    }
+                    if let Some(Ok(Some(name))) = &*single_sign_on.read() {
+                        a {
+                            href: "/oidc/login",
+                            { format!("Log in with {name}") }
+                        }
+                    }
+                ),
+            }
+            if let Some(err) = error() {
+                p {
+                    id: "account-error",
+                    { err }
+                }
+            }
+        }
+    )
+}
@@ -1,13 +1,23 @@
 use crate::prelude::*;
 use dioxus::prelude::*;
+use homescreen_data::prelude::{Caller, SectionWebsites};
 
 pub fn App() -> Element {
+    // The websites being shown are shared so accepting a suggestion shows it straight away
+    use_context_provider(|| Signal::new(Vec::<SectionWebsites>::new()));
+    // Typing in the search bar filters the websites shown below it
+    use_context_provider(|| Signal::new(SearchFilter::default()));
+    // Whoever is logged in, nothing for editing is shown to anonymous visitors
+    use_context_provider(|| Signal::new(None::<Caller>));
+
     rsx!(
         Header {}
         section {
             id: "traichu",
             SearchBar {}
             QuickSites {}
+            Suggestions {}
+            Account {}
         }
     )
 }
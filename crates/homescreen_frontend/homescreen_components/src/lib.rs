@@ -1,8 +1,10 @@
 #![allow(non_snake_case)]
 
+pub(crate) mod account;
 pub(crate) mod app;
 pub(crate) mod header;
 pub mod prelude;
 pub(crate) mod quicksites;
 pub(crate) mod search;
+pub(crate) mod suggestions;
 pub(crate) mod use_interval;
@@ -1,7 +1,9 @@
 pub use crate::{
+    account::{can_edit, Account},
     app::App,
     header::{Date, Header, Time},
-    quicksites::{QuickSite, QuickSiteColumn, QuickSiteColumns, QuickSites},
-    search::SearchBar,
+    quicksites::{QuickSite, QuickSiteColumn, QuickSiteColumns, QuickSites, Rearrange, TagChip},
+    search::{SearchBar, SearchFilter},
+    suggestions::{SuggestedSite, Suggestions},
     use_interval::use_interval,
 };
@@ -1,49 +1,248 @@
+use crate::prelude::{can_edit, SearchFilter};
 use dioxus::prelude::*;
 use homescreen_data::prelude::*;
-use homescreen_server_functions::prelude::get_websites;
+use homescreen_server_functions::prelude::{
+    get_shared_websites, get_websites, reorder_personal_websites, reorder_websites,
+};
 
-pub fn QuickSite(website: &Website) -> Element {
+/// Keeps track of the websites being shown and the one being dragged so they can be rearranged
+#[derive(Clone, Copy)]
+pub struct Rearrange {
+    shown: Signal<Vec<SectionWebsites>>,
+    dragged: Signal<Option<String>>,
+    caller: Signal<Option<Caller>>,
+    /// Set by people who can edit while they rearrange the shared homescreen instead of their own
+    shared: Signal<bool>,
+}
+impl Rearrange {
+    pub fn new(
+        shown: Signal<Vec<SectionWebsites>>,
+        dragged: Signal<Option<String>>,
+        caller: Signal<Option<Caller>>,
+        shared: Signal<bool>,
+    ) -> Self {
+        Self {
+            shown,
+            dragged,
+            caller,
+            shared,
+        }
+    }
+    /// Anyone logged in can rearrange their own homescreen, otherwise only people who can edit
+    /// are able to drag websites around
+    fn enabled(self) -> bool {
+        self.personal() || can_edit(self.caller)
+    }
+    /// Whether the shared homescreen is being rearranged, which stops as soon as whoever started
+    /// it can't edit anymore
+    fn editing_shared(self) -> bool {
+        *self.shared.read() && can_edit(self.caller)
+    }
+    /// Whether changes go to the personal homescreen of whoever is logged in rather than the
+    /// shared one
+    fn personal(self) -> bool {
+        !self.editing_shared() && self.caller.read().as_ref().is_some_and(Caller::is_user)
+    }
+    /// Moves the website being dragged into the section `slug` in front of `before`, or to the
+    /// end of the section when that is `None`. The page is changed straight away and put back if
+    /// the backend refuses the change
+    fn drop_website(mut self, slug: String, before: Option<String>) {
+        let Some(website_name) = self.dragged.take() else {
+            return;
+        };
+
+        let previous = self.shown.peek().clone();
+        let Some(order) = SectionWebsites::move_website(
+            &mut self.shown.write(),
+            &website_name,
+            &slug,
+            before.as_deref(),
+        ) else {
+            return;
+        };
+
+        let personal = self.personal();
+        spawn(async move {
+            let reordered = match personal {
+                true => reorder_personal_websites(order).await,
+                false => reorder_websites(order).await,
+            };
+            match reordered {
+                Ok(sections) => self.shown.set(sections),
+                Err(err) => {
+                    log::error!("{err}");
+                    self.shown.set(previous);
+                }
+            }
+        });
+    }
+}
+
+/// A tag shown next to a website, clicking it only shows websites with the same tag or shows
+/// everything again if that tag was already picked
+pub fn TagChip(tag: &str, mut filter: Signal<Option<String>>) -> Element {
+    let picked = filter.read().as_deref() == Some(tag);
+    let clicked = tag.to_owned();
+
+    rsx!(
+        button {
+            class: if picked { "tag picked" } else { "tag" },
+            prevent_default: "ondragover ondrop",
+            onclick: move |_| filter.set(if picked { None } else { Some(clicked.clone()) }),
+            { tag }
+        }
+    )
+}
+
+pub fn QuickSite(
+    website: &Website,
+    mut rearrange: Rearrange,
+    filter: Signal<Option<String>>,
+) -> Element {
+    let website_name = website.name().to_owned();
+    let slug = website.section().to_owned();
+
+    // The link is what actually gets dragged, so everything inside the li has to let it be
+    // dropped onto
     rsx!(
         li {
+            class: if website.is_personal() { "personal" } else { "" },
+            prevent_default: "ondragover ondrop",
+            ondragstart: {
+                let website_name = website_name.clone();
+                move |_| {
+                    if rearrange.enabled() {
+                        rearrange.dragged.set(Some(website_name.clone()));
+                    }
+                }
+            },
+            ondragover: |_| {},
+            ondrop: move |event| {
+                event.stop_propagation();
+                rearrange.drop_website(slug.clone(), Some(website_name.clone()));
+            },
             a {
-                href: format!("https://{}", website.link()),
+                href: website.go_link(),
+                draggable: if rearrange.enabled() { "true" } else { "false" },
+                prevent_default: "ondragover ondrop",
                 img {
                     class: "favicon",
                     src: website.icon_link(),
-                    alt: website.name()
+                    alt: website.name(),
+                    prevent_default: "ondragover ondrop",
                 }
                 { website.name() }
             },
+            { website.tags().iter().map(|tag| TagChip(tag, filter)) }
         }
     )
 }
 
-pub fn QuickSiteColumn(websites: &[Website], section: &WebsiteSection) -> Element {
+pub fn QuickSiteColumn(
+    websites: &[Website],
+    section: &WebsiteSection,
+    rearrange: Rearrange,
+    filter: Signal<Option<String>>,
+) -> Element {
+    let slug = section.slug().to_owned();
+
     rsx!(ul {
-        id: format!("{}-quicksites", section.to_string().to_lowercase()),
+        id: format!("{}-quicksites", section.slug()),
+        prevent_default: "ondragover ondrop",
+        ondragover: |_| {},
+        ondrop: move |_| rearrange.drop_website(slug.clone(), None),
         p {
             id: "sections",
-            { section.to_string() }
+            { section.display_name() }
         }
-        { websites.iter().map(QuickSite) }
+        { websites.iter().map(|website| QuickSite(website, rearrange, filter)) }
     })
 }
 
 pub fn QuickSiteColumns() -> Element {
     let response = use_server_future(get_websites)?;
+    let mut shown = use_context::<Signal<Vec<SectionWebsites>>>();
+    let caller = use_context::<Signal<Option<Caller>>>();
+    let mut shared = use_signal(|| false);
+    let rearrange = Rearrange::new(shown, use_signal(|| None), caller, shared);
+    let filter = use_signal(|| None::<String>);
+    let search = use_context::<Signal<SearchFilter>>();
+    let mut showing = use_signal(|| (None::<String>, false));
+
+    use_effect(move || {
+        if let Some(Ok(sections)) = &*response.read() {
+            shown.set(sections.clone());
+        }
+    });
+    // Logging in or out changes whose personal homescreen is layered over the shared one, and
+    // nobody's is while the shared homescreen is being edited
+    use_effect(move || {
+        let name = caller
+            .read()
+            .as_ref()
+            .map(|caller| caller.name().to_owned());
+        let editing_shared = rearrange.editing_shared();
+        if *showing.peek() == (name.clone(), editing_shared) {
+            return;
+        }
+        showing.set((name, editing_shared));
+        spawn(async move {
+            let sections = match editing_shared {
+                true => get_shared_websites().await,
+                false => get_websites().await,
+            };
+            match sections {
+                Ok(sections) => shown.set(sections),
+                Err(err) => log::error!("{err}"),
+            }
+        });
+    });
 
     response
         .read_unchecked()
         .as_ref()
         .map(|result| match result {
-            Ok(websites) => rsx!({
-                WebsiteSection::ALL
+            Ok(sections) => {
+                // Until the effect above has run the websites haven't been copied over yet
+                let shown = shown.read();
+                let sections = if shown.is_empty() { sections } else { &*shown };
+                let searched = search.read().apply(sections);
+                let sections = searched.as_deref().unwrap_or(sections);
+
+                // Sections without any websites with the picked tag are hidden while filtering
+                let picked = filter.read();
+                let columns = sections
                     .iter()
-                    .filter_map(|website_section| {
-                        websites.get(website_section).zip(Some(website_section))
-                    })
-                    .map(|(website, section)| QuickSiteColumn(website, section))
-            }),
+                    .filter_map(|column| match picked.as_deref() {
+                        Some(tag) => {
+                            let mut column = column.clone();
+                            column.retain_tagged(tag);
+                            (!column.websites().is_empty()).then_some(column)
+                        }
+                        None => Some(column.clone()),
+                    });
+
+                let editing_shared = rearrange.editing_shared();
+                rsx!(
+                    if can_edit(caller) {
+                        button {
+                            id: "edit-shared",
+                            r#type: "button",
+                            onclick: move |_| shared.set(!editing_shared),
+                            if editing_shared {
+                                "Done editing the shared homescreen"
+                            } else {
+                                "Edit the shared homescreen"
+                            }
+                        }
+                    }
+                    {
+                        columns.map(|column| {
+                            QuickSiteColumn(column.websites(), column.section(), rearrange, filter)
+                        })
+                    }
+                )
+            }
             Err(err) => {
                 log::error!("{err}");
                 rsx!(
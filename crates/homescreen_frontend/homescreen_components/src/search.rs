@@ -1,14 +1,130 @@
 use dioxus::prelude::*;
+use homescreen_data::prelude::*;
+use homescreen_server_functions::prelude::get_search_engines;
+
+/// What has been typed into the search bar, the websites on the page are filtered down to the
+/// ones it matches while typing
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct SearchFilter(String);
+impl SearchFilter {
+    /// The query to filter by, searches with a prefix are meant for a search engine so they don't
+    /// filter anything
+    pub fn query(&self) -> Option<&str> {
+        let query = self.0.trim();
+        (!query.is_empty() && !query.starts_with('!')).then_some(query)
+    }
+    /// The websites in `columns` that match, `None` if there is nothing to filter by or nothing
+    /// matches, in which case everything stays shown
+    pub fn apply(&self, columns: &[SectionWebsites]) -> Option<Vec<SectionWebsites>> {
+        let query = self.query()?;
+        let filtered: Vec<SectionWebsites> = columns
+            .iter()
+            .cloned()
+            .filter_map(|mut column| {
+                column.retain_matching(query);
+                (!column.websites().is_empty()).then_some(column)
+            })
+            .collect();
+
+        (!filtered.is_empty()).then_some(filtered)
+    }
+}
+
+/// Where a search goes. Input starting with a search engine's prefix such as `!crates serde` is
+/// searched for with that engine, input starting with an alias such as `gh homescreen` goes to
+/// that website's go link, input that only matches one website goes straight to it and anything
+/// else is searched for with `selected`
+fn search_link(
+    query: &str,
+    shown: &[SectionWebsites],
+    engines: &[SearchEngine],
+    selected: &SearchEngine,
+) -> Option<String> {
+    if let Some((engine, search)) = SearchEngine::pick(engines, query) {
+        return Some(engine.search_link(search));
+    }
+
+    let mut words = query.split_whitespace();
+    let alias = words.next()?.to_lowercase();
+    let arguments: Vec<&str> = words.collect();
+
+    let website = shown
+        .iter()
+        .flat_map(|column| column.websites())
+        .find(|website| website.alias() == Some(alias.as_str()));
+    if let Some(website) = website {
+        return Some(website.go_link_with(&arguments));
+    }
+
+    let filtered = SearchFilter(query.to_owned())
+        .apply(shown)
+        .unwrap_or_default();
+    match filtered
+        .iter()
+        .flat_map(|column| column.websites())
+        .collect::<Vec<_>>()[..]
+    {
+        [website] => Some(website.go_link()),
+        _ => Some(selected.search_link(query.trim())),
+    }
+}
 
 pub fn SearchBar() -> Element {
+    let response = use_server_future(get_search_engines)?;
+    let shown = use_context::<Signal<Vec<SectionWebsites>>>();
+    let mut filter = use_context::<Signal<SearchFilter>>();
+    let mut selected = use_signal(|| None::<usize>);
+
+    // Searches still work without the backend, they just go to DuckDuckGo like they used to
+    let engines = match &*response.read_unchecked() {
+        Some(Ok(engines)) if !engines.is_empty() => engines.clone(),
+        Some(Err(err)) => {
+            log::error!("{err}");
+            vec![SearchEngine::duckduckgo()]
+        }
+        _ => vec![SearchEngine::duckduckgo()],
+    };
+    let index = selected()
+        .or_else(|| engines.iter().position(SearchEngine::is_default))
+        .unwrap_or_default()
+        % engines.len();
+    let engine = engines[index].clone();
+    let name = engine.name().to_owned();
+    let prefix = engine.prefix().to_owned();
+    let count = engines.len();
+
     rsx!(
         section { id: "search",
             h2 { class: "sr-only", "Search" }
             form {
                 autocomplete: "off",
-                action: "https://duckduckgo.com/",
-                method: "get",
-                input { autofocus: "false", name: "q", r#type: "text", id: "q" }
+                prevent_default: "onsubmit",
+                onsubmit: move |event: FormEvent| {
+                    let query = event
+                        .values()
+                        .get("q")
+                        .map(FormValue::as_value)
+                        .unwrap_or_default();
+                    if let Some(link) = search_link(&query, &shown.read(), &engines, &engine) {
+                        // Debug formatting quotes the link and escapes anything that would end the string
+                        eval(&format!("window.location.href = {link:?};"));
+                    }
+                },
+                button {
+                    r#type: "button",
+                    id: "search-engine",
+                    title: "Search with the next search engine",
+                    onclick: move |_| selected.set(Some((index + 1) % count)),
+                    { name }
+                }
+                input {
+                    autofocus: "false",
+                    name: "q",
+                    r#type: "text",
+                    id: "q",
+                    placeholder: prefix,
+                    oninput: move |event| filter.set(SearchFilter(event.value())),
+                }
                 button { tabindex: "-1", class: "sr-only", "Search" }
             }
         }
@@ -0,0 +1,154 @@
+use crate::prelude::can_edit;
+use dioxus::prelude::*;
+use homescreen_data::prelude::*;
+use homescreen_server_functions::prelude::{add_website, suggest_websites};
+
+/// A host suggested from the browser history, it can be added to any section or dismissed
+pub fn SuggestedSite(
+    suggestion: &Suggestion,
+    mut suggestions: Signal<Vec<Suggestion>>,
+    mut shown: Signal<Vec<SectionWebsites>>,
+    mut error: Signal<Option<String>>,
+) -> Element {
+    let accepted = suggestion.clone();
+    let dismissed = suggestion.link().to_owned();
+
+    rsx!(
+        li {
+            form {
+                prevent_default: "onsubmit",
+                onsubmit: move |event: FormEvent| {
+                    let section = event
+                        .values()
+                        .get("section")
+                        .map(FormValue::as_value)
+                        .unwrap_or_default();
+                    let website = accepted.accept(section);
+                    let link = accepted.link().to_owned();
+
+                    async move {
+                        let saved = match website {
+                            Ok(website) => add_website(website).await.map_err(|err| err.to_string()),
+                            Err(err) => Err(err.to_string()),
+                        };
+                        match saved {
+                            Ok(sections) => {
+                                shown.set(sections);
+                                suggestions.write().retain(|suggestion| suggestion.link() != link);
+                            }
+                            Err(err) => {
+                                log::error!("{err}");
+                                error.set(Some(err));
+                            }
+                        }
+                    }
+                },
+                img {
+                    class: "favicon",
+                    src: format!("https://icons.duckduckgo.com/ip3/{}.ico", suggestion.link()),
+                    alt: suggestion.name(),
+                }
+                span {
+                    class: "suggestion-name",
+                    { suggestion.name() }
+                }
+                span {
+                    class: "suggestion-visits",
+                    { format!("{} visits", suggestion.visits()) }
+                }
+                select {
+                    name: "section",
+                    {
+                        shown.read().iter().map(|column| {
+                            let section = column.section();
+                            rsx!(
+                                option {
+                                    value: section.slug(),
+                                    selected: suggestion.section() == Some(section.slug()),
+                                    { section.display_name() }
+                                }
+                            )
+                        })
+                    }
+                }
+                button {
+                    r#type: "submit",
+                    "Add"
+                }
+                button {
+                    r#type: "button",
+                    onclick: move |_| {
+                        suggestions.write().retain(|suggestion| suggestion.link() != dismissed)
+                    },
+                    "Dismiss"
+                }
+            }
+        }
+    )
+}
+
+/// Suggests websites from a browser's history database, Firefox's `places.sqlite` or Chromium's
+/// `History`, so they can be reviewed one at a time
+pub fn Suggestions() -> Element {
+    let shown = use_context::<Signal<Vec<SectionWebsites>>>();
+    let caller = use_context::<Signal<Option<Caller>>>();
+    let mut suggestions = use_signal(Vec::<Suggestion>::new);
+    let mut error = use_signal(|| None::<String>);
+
+    // Accepting a suggestion adds a website, which only people who can edit are allowed to do
+    if !can_edit(caller) {
+        return None;
+    }
+
+    rsx!(
+        section {
+            id: "suggestions",
+            h2 {
+                class: "sr-only",
+                { "Suggestions" }
+            }
+            label {
+                "Suggest websites from browser history "
+                input {
+                    r#type: "file",
+                    onchange: move |event: FormEvent| async move {
+                        let Some(files) = event.files() else {
+                            return;
+                        };
+                        let Some(file) = files.files().into_iter().next() else {
+                            return;
+                        };
+                        let Some(history) = files.read_file(&file).await else {
+                            return;
+                        };
+
+                        match suggest_websites(history).await {
+                            Ok(found) => {
+                                error.set(None);
+                                suggestions.set(found);
+                            }
+                            Err(err) => {
+                                log::error!("{err}");
+                                error.set(Some(err.to_string()));
+                            }
+                        }
+                    },
+                }
+            }
+            if let Some(err) = error() {
+                p {
+                    id: "suggestion-error",
+                    { err }
+                }
+            }
+            ul {
+                {
+                    suggestions
+                        .read()
+                        .iter()
+                        .map(|suggestion| SuggestedSite(suggestion, suggestions, shown, error))
+                }
+            }
+        }
+    )
+}
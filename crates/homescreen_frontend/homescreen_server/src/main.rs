@@ -1,9 +1,82 @@
+use axum::{
+    extract::ConnectInfo,
+    http::{
+        header::{COOKIE, LOCATION, SET_COOKIE},
+        HeaderMap, StatusCode, Uri,
+    },
+    response::{IntoResponse, Response},
+    routing::get,
+};
 use dioxus::{
     dioxus_core::VirtualDom,
     prelude::{server_fn::axum::register_explicit, DioxusRouterExt, ServeConfig},
 };
 use homescreen_components::prelude::*;
 use homescreen_server_functions::prelude::*;
+use reqwest::redirect::Policy;
+use std::net::SocketAddr;
+
+/// Go links and single sign-on are handled by the backend, which only this server can reach, so
+/// the redirects it answers with are passed on to the browser along with any cookies it sets.
+/// Whoever is logged in is passed along too since they can go to their personal websites
+async fn redirect(
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    uri: Uri,
+    headers: HeaderMap,
+) -> Response {
+    let path = uri
+        .path_and_query()
+        .map_or(uri.path(), |path| path.as_str());
+    let proxy_auth = ProxyAuth::from_env();
+    let response = async {
+        let request = reqwest::Client::builder()
+            .redirect(Policy::none())
+            .build()?
+            .get(format!("http://127.0.0.1:8888{path}"));
+        let proxied_user = proxy_auth.as_ref().and_then(|proxy_auth| {
+            Some((
+                proxy_auth.header(),
+                proxy_auth.proxied_user(Some(peer.ip()), &headers)?,
+            ))
+        });
+        let request = match proxied_user {
+            Some((header, name)) => request.header(header, name.as_bytes()),
+            None => request,
+        };
+        match headers.get(COOKIE) {
+            Some(cookies) => request.header(COOKIE, cookies.as_bytes()),
+            None => request,
+        }
+        .send()
+        .await
+    }
+    .await;
+
+    let response = match response {
+        Ok(response) => response,
+        Err(err) => return (StatusCode::BAD_GATEWAY, err.to_string()).into_response(),
+    };
+    let mut passed_on = HeaderMap::new();
+    for cookie in response.headers().get_all(SET_COOKIE) {
+        if let Ok(cookie) = cookie.as_bytes().try_into() {
+            passed_on.append(SET_COOKIE, cookie);
+        }
+    }
+    match response.headers().get(LOCATION) {
+        Some(location) => {
+            if let Ok(location) = location.as_bytes().try_into() {
+                passed_on.insert(LOCATION, location);
+            }
+            (StatusCode::FOUND, passed_on).into_response()
+        }
+        None => (
+            response.status(),
+            passed_on,
+            response.text().await.unwrap_or_default(),
+        )
+            .into_response(),
+    }
+}
 
 #[tokio::main]
 async fn main() {
@@ -12,13 +85,25 @@ async fn main() {
         .unwrap();
 
     register_explicit::<GetWebsites>();
+    register_explicit::<ReorderWebsites>();
+    register_explicit::<ReorderPersonalWebsites>();
+    register_explicit::<SuggestWebsites>();
+    register_explicit::<SaveWebsite>();
+    register_explicit::<GetSearchEngines>();
+    register_explicit::<GetSession>();
+    register_explicit::<LogIn>();
+    register_explicit::<LogOut>();
+    register_explicit::<GetSingleSignOn>();
 
     axum::serve(
         listener,
         axum::Router::new()
+            .route("/go/*path", get(redirect))
+            .route("/oidc/login", get(redirect))
+            .route("/oidc/callback", get(redirect))
             .serve_dioxus_application(ServeConfig::builder().build(), || VirtualDom::new(App))
             .await
-            .into_make_service(),
+            .into_make_service_with_connect_info::<SocketAddr>(),
     )
     .await
     .unwrap()
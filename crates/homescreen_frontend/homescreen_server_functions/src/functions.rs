@@ -1,13 +1,112 @@
-use std::collections::HashMap;
-
-use dioxus::prelude::*;
-use homescreen_data::prelude::{Website, WebsiteSection};
+use dioxus::prelude::{server_fn::codec::Json, *};
+use homescreen_data::prelude::{
+    Caller, SearchEngine, SectionWebsites, Suggestion, Website, WebsiteOrder,
+};
 use homescreen_errors::prelude::*;
 
-type WebsiteCollection = HashMap<WebsiteSection, Vec<Website>>;
+type WebsiteCollection = Vec<SectionWebsites>;
+
+/// The cookie the backend keeps the session of whoever logged in in
+#[cfg(feature = "server")]
+const SESSION_COOKIE: &str = "homescreen_session";
+
+/// The cookies the browser sent along with the server function call, which includes its session
+/// once it has logged in
+#[cfg(feature = "server")]
+async fn browser_cookies() -> Option<reqwest::header::HeaderValue> {
+    let context = server_context();
+    let parts = context.request_parts().await;
+    let cookies = parts.headers.get(reqwest::header::COOKIE)?;
+    reqwest::header::HeaderValue::from_bytes(cookies.as_bytes()).ok()
+}
+
+/// The header a reverse proxy put the name of whoever it authenticated in, so it can be passed on
+/// to the backend. Only requests that came straight from a trusted proxy have one, see
+/// [`crate::proxy::ProxyAuth::proxied_user`]
+#[cfg(feature = "server")]
+async fn proxied_user() -> Option<(String, reqwest::header::HeaderValue)> {
+    let proxy_auth = crate::proxy::ProxyAuth::from_env()?;
+    let context = server_context();
+    let parts = context.request_parts().await;
+    let peer = parts
+        .extensions
+        .get::<axum::extract::ConnectInfo<std::net::SocketAddr>>()
+        .map(|axum::extract::ConnectInfo(peer)| peer.ip());
+    let name = proxy_auth.proxied_user(peer, &parts.headers)?.clone();
+    Some((proxy_auth.header().to_owned(), name))
+}
+
+/// Passes the browser's session, or the user the proxy in front of this server authenticated, on
+/// to the backend so requests are made as whoever is logged in
+#[cfg(feature = "server")]
+async fn with_session(request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+    let request = match proxied_user().await {
+        Some((header, name)) => request.header(header, name),
+        None => request,
+    };
+    match browser_cookies().await {
+        Some(cookies) => request.header(reqwest::header::COOKIE, cookies),
+        None => request,
+    }
+}
+
+/// Passes whoever is logged in on to the backend for requests that change something. Only
+/// credentials that came from the browser are sent, so browsers that nobody has logged in on get
+/// a 401 without the request ever reaching the backend
+#[cfg(feature = "server")]
+async fn authorized(
+    request: reqwest::RequestBuilder,
+) -> Result<reqwest::RequestBuilder, FrontendError> {
+    let has_session = browser_cookies().await.is_some_and(|cookies| {
+        cookies.to_str().is_ok_and(|cookies| {
+            cookies
+                .split(';')
+                .any(|cookie| cookie.trim().starts_with(&format!("{SESSION_COOKIE}=")))
+        })
+    });
+    if !has_session && proxied_user().await.is_none() {
+        if let Ok(mut parts) = server_context().response_parts_mut() {
+            parts.status = reqwest::StatusCode::UNAUTHORIZED;
+        }
+        return Err(FrontendError::NotLoggedIn);
+    }
+
+    Ok(with_session(request).await)
+}
 
+/// Passes the cookies the backend set on to the browser, which is how it gets its session
+#[cfg(feature = "server")]
+fn pass_on_cookies(response: &reqwest::Response) {
+    let context = server_context();
+    let Ok(mut parts) = context.response_parts_mut() else {
+        return;
+    };
+    for cookie in response.headers().get_all(reqwest::header::SET_COOKIE) {
+        if let Ok(cookie) = cookie.as_bytes().try_into() {
+            parts.headers.append(reqwest::header::SET_COOKIE, cookie);
+        }
+    }
+}
+
+/// The shared homescreen, with the personal homescreen of whoever is logged in layered over it
 #[server(GetWebsites)]
 pub async fn get_websites() -> Result<WebsiteCollection, ServerFnError> {
+    let response = with_session(reqwest::Client::new().get("http://127.0.0.1:8888/websites"))
+        .await
+        .send()
+        .await
+        .map_err(FrontendError::CannotRetrieveWebsites)?
+        .json::<WebsiteCollection>()
+        .await
+        .map_err(FrontendError::InvalidResponseRecieved)?;
+
+    Ok(response)
+}
+
+/// The shared homescreen on its own, without anyone's personal homescreen over it, which is what
+/// people who can edit rearrange
+#[server(GetSharedWebsites)]
+pub async fn get_shared_websites() -> Result<WebsiteCollection, ServerFnError> {
     let response = reqwest::get("http://127.0.0.1:8888/websites")
         .await
         .map_err(FrontendError::CannotRetrieveWebsites)?
@@ -17,3 +116,201 @@ pub async fn get_websites() -> Result<WebsiteCollection, ServerFnError> {
 
     Ok(response)
 }
+
+/// Sends the new order of websites to the backend, the websites are returned in the order the
+/// backend ended up with
+#[server(ReorderWebsites, input = Json)]
+pub async fn reorder_websites(order: WebsiteOrder) -> Result<WebsiteCollection, ServerFnError> {
+    let response =
+        authorized(reqwest::Client::new().post("http://127.0.0.1:8888/websites/reorder"))
+            .await?
+            .json(&order)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(FrontendError::CannotReorderWebsites)?
+            .json::<WebsiteCollection>()
+            .await
+            .map_err(FrontendError::InvalidResponseRecieved)?;
+
+    Ok(response)
+}
+
+/// Sends the new order of websites to the backend as a change to the personal homescreen of
+/// whoever is logged in, nobody else sees it
+#[server(ReorderPersonalWebsites, input = Json)]
+pub async fn reorder_personal_websites(
+    order: WebsiteOrder,
+) -> Result<WebsiteCollection, ServerFnError> {
+    let response =
+        with_session(reqwest::Client::new().post("http://127.0.0.1:8888/personal/reorder"))
+            .await
+            .json(&order)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(FrontendError::CannotReorderWebsites)?
+            .json::<WebsiteCollection>()
+            .await
+            .map_err(FrontendError::InvalidResponseRecieved)?;
+
+    Ok(response)
+}
+
+/// Sends a browser's history database to the backend, which suggests the hosts visited the most
+/// that aren't on the homescreen yet
+#[server(SuggestWebsites, input = Json)]
+pub async fn suggest_websites(history: Vec<u8>) -> Result<Vec<Suggestion>, ServerFnError> {
+    let response = authorized(reqwest::Client::new().post("http://127.0.0.1:8888/suggestions"))
+        .await?
+        .body(history)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(FrontendError::CannotSuggestWebsites)?
+        .json::<Vec<Suggestion>>()
+        .await
+        .map_err(FrontendError::InvalidResponseRecieved)?;
+
+    Ok(response)
+}
+
+/// Sends `website` as the form the backend reads websites from
+#[cfg(feature = "server")]
+async fn send_website(
+    request: reqwest::RequestBuilder,
+    website: &Website,
+) -> Result<(), FrontendError> {
+    let tags = website.tags().iter().cloned().collect::<Vec<_>>().join(",");
+    authorized(request)
+        .await?
+        .form(&[
+            ("website_name", website.name()),
+            ("website_link", website.link()),
+            ("section", website.section()),
+            ("tags", &tags),
+            ("alias", website.alias().unwrap_or_default()),
+            ("link_template", website.link_template().unwrap_or_default()),
+        ])
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(FrontendError::CannotSaveWebsite)?;
+
+    Ok(())
+}
+
+/// Creates the website, or updates the one with the same name, and returns every website
+/// afterwards
+#[server(SaveWebsite, input = Json)]
+pub async fn save_website(website: Website) -> Result<WebsiteCollection, ServerFnError> {
+    send_website(
+        reqwest::Client::new().put("http://127.0.0.1:8888/websites"),
+        &website,
+    )
+    .await?;
+
+    get_websites().await
+}
+
+/// Creates the website and returns every website afterwards, failing rather than changing the
+/// website if there is already one with the same name
+#[server(AddWebsite, input = Json)]
+pub async fn add_website(website: Website) -> Result<WebsiteCollection, ServerFnError> {
+    send_website(
+        reqwest::Client::new().post("http://127.0.0.1:8888/websites"),
+        &website,
+    )
+    .await?;
+
+    get_websites().await
+}
+
+#[server(GetSearchEngines)]
+pub async fn get_search_engines() -> Result<Vec<SearchEngine>, ServerFnError> {
+    let response = reqwest::get("http://127.0.0.1:8888/search-engines")
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(FrontendError::CannotRetrieveSearchEngines)?
+        .json::<Vec<SearchEngine>>()
+        .await
+        .map_err(FrontendError::InvalidResponseRecieved)?;
+
+    Ok(response)
+}
+
+/// Who is logged in, `None` for anonymous visitors. The api token isn't sent so the frontend only
+/// shows anything for editing to people who have logged in
+#[server(GetSession)]
+pub async fn get_session() -> Result<Option<Caller>, ServerFnError> {
+    let response = with_session(reqwest::Client::new().get("http://127.0.0.1:8888/session"))
+        .await
+        .send()
+        .await
+        .map_err(FrontendError::CannotRetrieveSession)?;
+    if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+        return Ok(None);
+    }
+
+    let caller = response
+        .error_for_status()
+        .map_err(FrontendError::CannotRetrieveSession)?
+        .json::<Caller>()
+        .await
+        .map_err(FrontendError::InvalidResponseRecieved)?;
+
+    Ok(Some(caller))
+}
+
+#[server(LogIn)]
+pub async fn log_in(name: String, password: String) -> Result<Caller, ServerFnError> {
+    let response = reqwest::Client::new()
+        .post("http://127.0.0.1:8888/login")
+        .form(&[("name", name), ("password", password)])
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(FrontendError::CannotLogIn)?;
+    pass_on_cookies(&response);
+
+    let caller = response
+        .json::<Caller>()
+        .await
+        .map_err(FrontendError::InvalidResponseRecieved)?;
+
+    Ok(caller)
+}
+
+#[server(LogOut)]
+pub async fn log_out() -> Result<(), ServerFnError> {
+    let response = with_session(reqwest::Client::new().post("http://127.0.0.1:8888/logout"))
+        .await
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(FrontendError::CannotLogOut)?;
+    pass_on_cookies(&response);
+
+    Ok(())
+}
+
+/// The name of the identity provider people can log in through, `None` when the backend doesn't
+/// have one configured
+#[server(GetSingleSignOn)]
+pub async fn get_single_sign_on() -> Result<Option<String>, ServerFnError> {
+    let response = reqwest::get("http://127.0.0.1:8888/oidc")
+        .await
+        .map_err(FrontendError::CannotRetrieveSingleSignOn)?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+
+    let mut single_sign_on = response
+        .error_for_status()
+        .map_err(FrontendError::CannotRetrieveSingleSignOn)?
+        .json::<std::collections::HashMap<String, String>>()
+        .await
+        .map_err(FrontendError::InvalidResponseRecieved)?;
+
+    Ok(single_sign_on.remove("name"))
+}
@@ -1 +1,8 @@
-pub use crate::functions::{get_websites, GetWebsites};
+pub use crate::functions::{
+    add_website, get_search_engines, get_session, get_shared_websites, get_single_sign_on,
+    get_websites, log_in, log_out, reorder_personal_websites, reorder_websites, save_website,
+    suggest_websites, AddWebsite, GetSearchEngines, GetSession, GetSharedWebsites, GetSingleSignOn,
+    GetWebsites, LogIn, LogOut, ReorderPersonalWebsites, ReorderWebsites, SaveWebsite,
+    SuggestWebsites,
+};
+pub use crate::proxy::ProxyAuth;
@@ -0,0 +1,69 @@
+use homescreen_data::prelude::TrustedProxies;
+use reqwest::header::{HeaderMap, HeaderValue};
+use std::net::IpAddr;
+
+/// The reverse proxy in front of the frontend server that says who is logged in, read from
+/// `HOMESCREEN_PROXY_AUTH_HEADER` and `HOMESCREEN_TRUSTED_PROXIES`
+#[derive(Clone, Debug)]
+pub struct ProxyAuth {
+    header: String,
+    trusted_proxies: TrustedProxies,
+}
+impl ProxyAuth {
+    pub fn new(header: String, trusted_proxies: TrustedProxies) -> Self {
+        Self {
+            header,
+            trusted_proxies,
+        }
+    }
+    /// `None` unless `HOMESCREEN_PROXY_AUTH_HEADER` is set. `HOMESCREEN_TRUSTED_PROXIES` is a comma
+    /// separated list of address ranges, anything in it that isn't one is left out so it can only
+    /// ever trust less
+    pub fn from_env() -> Option<Self> {
+        let header = std::env::var("HOMESCREEN_PROXY_AUTH_HEADER").ok()?;
+        let trusted_proxies = std::env::var("HOMESCREEN_TRUSTED_PROXIES")
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|range| range.trim().parse().ok())
+            .collect();
+
+        Some(Self::new(header, TrustedProxies::new(trusted_proxies)))
+    }
+    pub fn header(&self) -> &str {
+        &self.header
+    }
+    /// The name in the header, only when the request came straight from a trusted proxy. Anyone
+    /// else could have made the header up, so it is never passed on to the backend for them
+    ///
+    /// ```rust
+    /// # use homescreen_data::prelude::TrustedProxies;
+    /// # use homescreen_server_functions::prelude::*;
+    /// # use reqwest::header::HeaderMap;
+    /// let proxy_auth = ProxyAuth::new(
+    ///     "Remote-User".into(),
+    ///     TrustedProxies::new(vec!["127.0.0.1/32".parse().unwrap()]),
+    /// );
+    /// let mut headers = HeaderMap::new();
+    /// headers.insert("Remote-User", "admin".parse().unwrap());
+    ///
+    /// assert_eq!(proxy_auth.proxied_user(Some("127.0.0.1".parse().unwrap()), &headers).unwrap(), "admin");
+    /// assert!(proxy_auth.proxied_user(Some("::ffff:127.0.0.1".parse().unwrap()), &headers).is_some());
+    /// // A browser reaching the frontend some other way can't pretend to be someone
+    /// assert!(proxy_auth.proxied_user(Some("203.0.113.7".parse().unwrap()), &headers).is_none());
+    /// assert!(proxy_auth.proxied_user(None, &headers).is_none());
+    /// assert!(ProxyAuth::new("Remote-User".into(), TrustedProxies::default())
+    ///     .proxied_user(Some("127.0.0.1".parse().unwrap()), &headers)
+    ///     .is_none());
+    /// ```
+    pub fn proxied_user<'a>(
+        &self,
+        peer: Option<IpAddr>,
+        headers: &'a HeaderMap,
+    ) -> Option<&'a HeaderValue> {
+        if !self.trusted_proxies.trusts(peer?) {
+            return None;
+        }
+
+        headers.get(self.header.as_str())
+    }
+}